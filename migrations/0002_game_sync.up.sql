@@ -0,0 +1,2 @@
+alter table games
+    add column auto_sync text check (auto_sync in ('role', 'players'));
@@ -0,0 +1,7 @@
+create table if not exists game_channels (
+    channel_id bigint primary key,
+    game_id int not null references games(id) on delete cascade,
+    guild_id bigint not null,
+
+    purpose text not null
+);
@@ -0,0 +1,3 @@
+drop table guild_settings;
+
+alter table games drop column nickname_template;
@@ -0,0 +1,8 @@
+alter table games
+    add column nickname_template text;
+
+create table if not exists guild_settings (
+    guild_id bigint primary key,
+
+    nickname_template text
+);
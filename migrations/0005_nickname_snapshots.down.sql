@@ -0,0 +1 @@
+drop table nickname_snapshots;
@@ -0,0 +1,8 @@
+create table if not exists nickname_snapshots (
+    guild_id bigint not null,
+    user_id bigint not null,
+
+    nickname text,
+
+    primary key (guild_id, user_id)
+);
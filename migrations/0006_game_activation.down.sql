@@ -0,0 +1 @@
+alter table games drop column active_since;
@@ -0,0 +1,2 @@
+alter table games
+    add column active_since timestamp with time zone;
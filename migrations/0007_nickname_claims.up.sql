@@ -0,0 +1,9 @@
+create table if not exists nickname_claims (
+    game_id int references games(id) on delete cascade,
+    user_id bigint,
+    guild_id bigint not null,
+
+    claimed_at timestamp with time zone not null default (now() at time zone 'utc'),
+
+    primary key (game_id, user_id)
+);
@@ -0,0 +1,9 @@
+create table if not exists transfer_offers (
+    game_id int primary key references games(id) on delete cascade,
+
+    owner_id bigint not null,
+    recipient_id bigint not null,
+    also_leave boolean not null default false,
+
+    expires_at timestamp with time zone not null
+);
@@ -0,0 +1,12 @@
+create table if not exists invites (
+    id int primary key generated always as identity,
+    game_id int not null references games(id) on delete cascade,
+    guild_id bigint not null,
+
+    user_id bigint not null,
+    invited_by bigint not null,
+
+    created_at timestamp with time zone not null default (now() at time zone 'utc'),
+
+    unique (game_id, user_id)
+);
@@ -0,0 +1,2 @@
+alter table games
+    add column open boolean not null default false;
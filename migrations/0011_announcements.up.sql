@@ -0,0 +1,17 @@
+create table if not exists announcements (
+    id int primary key generated always as identity,
+    game_id int not null references games(id) on delete cascade,
+    guild_id bigint not null,
+    author_id bigint not null,
+
+    channel_id bigint not null,
+    message_id bigint,
+
+    title text not null,
+    content text not null,
+
+    post_at timestamp with time zone not null default (now() at time zone 'utc'),
+    posted_at timestamp with time zone,
+
+    created_at timestamp with time zone not null default (now() at time zone 'utc')
+);
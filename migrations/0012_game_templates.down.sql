@@ -0,0 +1,2 @@
+drop table template_characters;
+drop table game_templates;
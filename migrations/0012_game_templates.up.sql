@@ -0,0 +1,28 @@
+create table if not exists game_templates (
+    id int primary key generated always as identity,
+    guild_id bigint not null,
+
+    name text not null,
+
+    system_id int references systems(id) on delete set null,
+    description text,
+    image text,
+    nickname_template text,
+
+    created_at timestamp with time zone not null default (now() at time zone 'utc'),
+
+    unique (name, guild_id)
+);
+
+create table if not exists template_characters (
+    template_id int not null references game_templates(id) on delete cascade,
+
+    name text not null,
+    pronouns text,
+
+    image text,
+
+    description text,
+
+    unique (name, template_id)
+);
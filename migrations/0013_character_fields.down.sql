@@ -0,0 +1,8 @@
+alter table template_characters
+drop column fields;
+
+alter table characters
+drop column fields;
+
+alter table systems
+drop column fields;
@@ -0,0 +1,8 @@
+alter table systems
+add column fields jsonb not null default '[]';
+
+alter table characters
+add column fields jsonb not null default '{}';
+
+alter table template_characters
+add column fields jsonb not null default '{}';
@@ -0,0 +1,16 @@
+create table if not exists rolls (
+    id int primary key generated always as identity,
+    game_id int not null references games(id) on delete cascade,
+
+    user_id bigint not null,
+    character_id int references characters(id) on delete set null,
+
+    expression text not null,
+    total bigint not null,
+    -- The dice in each group, as written by `dice::Roll`.
+    groups jsonb not null,
+
+    rolled_at timestamp with time zone not null default (now() at time zone 'utc')
+);
+
+create index if not exists rolls_game_id_rolled_at on rolls (game_id, rolled_at desc);
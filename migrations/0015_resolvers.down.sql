@@ -0,0 +1,5 @@
+alter table rolls
+drop column outcome;
+
+alter table systems
+drop column resolver;
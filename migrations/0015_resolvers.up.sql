@@ -0,0 +1,5 @@
+alter table systems
+add column resolver text;
+
+alter table rolls
+add column outcome text;
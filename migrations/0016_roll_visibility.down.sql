@@ -0,0 +1,2 @@
+alter table rolls
+drop column visibility;
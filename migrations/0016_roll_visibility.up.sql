@@ -0,0 +1,2 @@
+alter table rolls
+add column visibility text not null default 'public';
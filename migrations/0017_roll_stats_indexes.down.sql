@@ -0,0 +1,2 @@
+drop index rolls_character_id_rolled_at;
+drop index rolls_game_id_user_id_rolled_at;
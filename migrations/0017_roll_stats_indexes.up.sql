@@ -0,0 +1,3 @@
+-- For summarising a player's or a character's rolls with `/roll stats`.
+create index if not exists rolls_game_id_user_id_rolled_at on rolls (game_id, user_id, rolled_at desc);
+create index if not exists rolls_character_id_rolled_at on rolls (character_id, rolled_at desc);
@@ -0,0 +1,2 @@
+alter table announcements
+drop column failed_at;
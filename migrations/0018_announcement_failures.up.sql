@@ -0,0 +1,3 @@
+-- Scheduled announcements that couldn't be posted, which the scheduler won't try again.
+alter table announcements
+add column failed_at timestamp with time zone;
@@ -0,0 +1,182 @@
+use serenity::all::{
+    ChannelId, Context, CreateAllowedMentions, CreateEmbed, CreateEmbedFooter, CreateMessage,
+    EditMessage, Http, Mentionable, Message, MessageId, RoleId, UserId,
+};
+use sqlx::{
+    query,
+    types::chrono::{DateTime, NaiveDateTime, Utc},
+};
+
+use crate::{events::notify, Result, DB};
+
+/// The format scheduled times are written in.
+pub const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Parse a scheduled time, written in UTC as [`TIME_FORMAT`].
+pub fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value.trim(), TIME_FORMAT)
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+fn embed(title: &str, content: &str, game_title: &str) -> CreateEmbed {
+    CreateEmbed::new()
+        .title(title)
+        .description(content)
+        .footer(CreateEmbedFooter::new(game_title))
+}
+
+/// Send an announcement to its channel, pinging the game's role, without marking it as posted.
+async fn send(http: &Http, pool: &DB, announcement: i32) -> Result<Message> {
+    let record = query!(
+        r#"
+        select
+            a.title, a.content, a.channel_id,
+            g.title as "game_title", g.role_id
+        from announcements as a
+        join games as g on g.id = a.game_id
+        where a.id = $1
+        "#,
+        announcement,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let channel_id = ChannelId::from(record.channel_id as u64);
+    let role_id = RoleId::from(record.role_id as u64);
+
+    let message = channel_id
+        .send_message(
+            http,
+            CreateMessage::new()
+                .content(role_id.mention().to_string())
+                .embed(embed(&record.title, &record.content, &record.game_title))
+                .allowed_mentions(CreateAllowedMentions::new().roles(vec![role_id])),
+        )
+        .await?;
+
+    Ok(message)
+}
+
+/// Post an announcement to its channel, pinging the game's role.
+pub async fn post(http: &Http, pool: &DB, announcement: i32) -> Result<(ChannelId, MessageId)> {
+    let message = send(http, pool, announcement).await?;
+
+    query!(
+        r#"
+        update announcements
+        set
+            message_id = $2,
+            posted_at = now(),
+            failed_at = null
+        where id = $1
+        "#,
+        announcement,
+        message.id.get() as i64,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok((message.channel_id, message.id))
+}
+
+/// Bring an announcement's most recent post up to date with its content, if it has been posted.
+pub async fn update(http: &Http, pool: &DB, announcement: i32) -> Result<()> {
+    let record = query!(
+        r#"
+        select
+            a.title, a.content, a.channel_id, a.message_id,
+            g.title as "game_title"
+        from announcements as a
+        join games as g on g.id = a.game_id
+        where a.id = $1
+        "#,
+        announcement,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if let Some(message_id) = record.message_id {
+        ChannelId::from(record.channel_id as u64)
+            .edit_message(
+                http,
+                MessageId::from(message_id as u64),
+                EditMessage::new().embed(embed(&record.title, &record.content, &record.game_title)),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Post every scheduled announcement that is due.
+///
+/// Any that fail are marked as failed and their game's owner is told, so the rest still go out
+/// and the failed ones can be posted with `/game announce repost`.
+pub async fn post_due(ctx: &Context, pool: &DB) -> Result<()> {
+    loop {
+        let mut txn = pool.begin().await?;
+
+        // Each announcement stays locked until it's marked, so a slow run can't post it twice.
+        let Some(due) = query!(
+            r#"
+            select a.id, a.title, g.title as "game_title", g.owner_id
+            from announcements as a
+            join games as g on g.id = a.game_id
+            where a.posted_at is null and a.failed_at is null and a.post_at <= now()
+            order by a.post_at
+            limit 1
+            for update of a skip locked
+            "#
+        )
+        .fetch_optional(&mut *txn)
+        .await?
+        else {
+            break;
+        };
+
+        match send(&ctx.http, pool, due.id).await {
+            Ok(message) => {
+                query!(
+                    r#"
+                    update announcements
+                    set
+                        message_id = $2,
+                        posted_at = now()
+                    where id = $1
+                    "#,
+                    due.id,
+                    message.id.get() as i64,
+                )
+                .execute(&mut *txn)
+                .await?;
+            }
+            Err(e) => {
+                println!("Error posting announcement {}: {}", due.id, e);
+                query!(
+                    r#"
+                    update announcements
+                    set failed_at = now()
+                    where id = $1
+                    "#,
+                    due.id,
+                )
+                .execute(&mut *txn)
+                .await?;
+                notify(
+                    ctx,
+                    UserId::from(due.owner_id as u64),
+                    format!(
+                        "I couldn't post the announcement `{}` for `{}`. Check my permissions in its channel, then try `/game announce repost`.",
+                        due.title, due.game_title,
+                    ),
+                )
+                .await;
+            }
+        }
+
+        txn.commit().await?;
+    }
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use serenity::all::AutocompleteChoice;
 use sqlx::query;
 
-use crate::Context;
+use crate::{resolvers::RESOLVERS, Context};
 
 fn search_terms(partial: &str) -> String {
     format!(
@@ -157,6 +157,170 @@ pub async fn game_joined(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteCho
     .collect()
 }
 
+pub async fn game_open(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    query!(
+        r#"
+        select
+            id, title
+        from games as g
+        where
+            guild_id = $1
+            and
+            open
+            and
+            owner_id != $4
+            and
+            not exists (
+                select 1
+                from players
+                where
+                    user_id = $4
+                    and
+                    game_id = g.id
+            )
+            and
+            (
+                $3 = ''
+                or
+                to_tsvector(title) @@ to_tsquery($2)
+                or
+                to_tsvector(abbreviation) @@ to_tsquery($2)
+            )
+        limit 25
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        search_terms(partial),
+        partial,
+        ctx.author().id.get() as i64,
+    )
+    .fetch_all(&ctx.data().pool)
+    .await
+    .unwrap()
+    .into_iter()
+    .map(|record| AutocompleteChoice::new(record.title, record.id))
+    .collect()
+}
+
+pub async fn game_playing(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    query!(
+        r#"
+        select
+            id, title
+        from games as g
+        where
+            guild_id = $1
+            and
+            exists (
+                select 1
+                from players
+                where
+                    user_id = $4
+                    and
+                    game_id = g.id
+            )
+            and
+            (
+                $3 = ''
+                or
+                to_tsvector(title) @@ to_tsquery($2)
+                or
+                to_tsvector(abbreviation) @@ to_tsquery($2)
+            )
+        limit 25
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        search_terms(partial),
+        partial,
+        ctx.author().id.get() as i64,
+    )
+    .fetch_all(&ctx.data().pool)
+    .await
+    .unwrap()
+    .into_iter()
+    .map(|record| AutocompleteChoice::new(record.title, record.id))
+    .collect()
+}
+
+pub async fn announcement_editable(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    query!(
+        r#"
+        select
+            a.id, a.title, g.abbreviation
+        from announcements as a
+        join games as g on g.id = a.game_id
+        where
+            a.guild_id = $1
+            and
+            ($4 or g.owner_id = $5)
+            and
+            (
+                $3 = ''
+                or
+                to_tsvector(a.title) @@ to_tsquery($2)
+            )
+        order by a.created_at desc
+        limit 25
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        search_terms(partial),
+        partial,
+        is_mod(&ctx).await,
+        ctx.author().id.get() as i64,
+    )
+    .fetch_all(&ctx.data().pool)
+    .await
+    .unwrap()
+    .into_iter()
+    .map(|record| {
+        AutocompleteChoice::new(
+            format!("[{}] {}", record.abbreviation, record.title),
+            record.id,
+        )
+    })
+    .collect()
+}
+
+pub async fn template(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    query!(
+        r#"
+        select
+            id, name
+        from game_templates
+        where
+            guild_id = $1
+            and (
+                $3 = ''
+                or
+                to_tsvector(name) @@ to_tsquery($2)
+            )
+        limit 25
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        search_terms(partial),
+        partial,
+    )
+    .fetch_all(&ctx.data().pool)
+    .await
+    .unwrap()
+    .into_iter()
+    .map(|record| AutocompleteChoice::new(record.name, record.id))
+    .collect()
+}
+
+pub async fn resolver(_: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    let partial = partial.to_lowercase();
+    RESOLVERS
+        .iter()
+        .filter(|r| {
+            r.description().to_lowercase().contains(&partial) || r.name().contains(&partial)
+        })
+        .map(|r| {
+            let label: String = r.description().chars().take(100).collect();
+            AutocompleteChoice::new(label, r.name())
+        })
+        .collect()
+}
+
 pub async fn character(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
     query!(
         r#"
@@ -8,7 +8,8 @@ async fn main() -> eyre::Result<()> {
     dotenv()?;
 
     let token = env::var("DISCORD_TOKEN")?;
-    let intents = serenity::GatewayIntents::non_privileged();
+    let intents =
+        serenity::GatewayIntents::non_privileged() | serenity::GatewayIntents::GUILD_MEMBERS;
 
     let db_url = env::var("DATABASE_URL")?;
 
@@ -16,16 +17,21 @@ async fn main() -> eyre::Result<()> {
         .options(poise::FrameworkOptions {
             commands: eurydice::commands::all(),
             on_error: |error| Box::pin(eurydice::error::handle(error)),
+            event_handler: |ctx, event, framework, data| {
+                Box::pin(eurydice::events::handle(ctx, event, framework, data))
+            },
             ..Default::default()
         })
-        .setup(move |_ctx, _ready, _framework| {
+        .setup(move |ctx, _ready, _framework| {
             Box::pin(async move {
-                Ok(eurydice::Data {
-                    pool: PgPoolOptions::new()
-                        .max_connections(5)
-                        .connect(&db_url)
-                        .await?,
-                })
+                let pool = PgPoolOptions::new()
+                    .max_connections(5)
+                    .connect(&db_url)
+                    .await?;
+
+                eurydice::jobs::spawn(ctx.clone(), pool.clone());
+
+                Ok(eurydice::Data { pool })
             })
         })
         .build();
@@ -0,0 +1,440 @@
+//! A game and everything in it, as a single JSON document.
+//!
+//! Bundles are made by `/game export` and read back by `/game import`, so anything written here
+//! has to survive the round trip. Discord ids are written as strings, since they don't fit in a
+//! JavaScript number.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "game": {
+//!     "title": "Blades in the Dark",
+//!     "abbreviation": "BitD",
+//!     "description": null,
+//!     "image": null,
+//!     "owner_id": "80351110224678912",
+//!     "role_id": "1034512349523456789",
+//!     "main_channel_id": null,
+//!     "channels": [{ "channel_id": "1034512349523456790", "purpose": "OOC" }],
+//!     "nickname_template": null,
+//!     "open": false,
+//!     "created_at": "2024-10-31T19:30:00Z"
+//!   },
+//!   "system": {
+//!     "title": "Blades in the Dark", "abbreviation": "BitD", "description": null, "image": null, "link": null,
+//!     "fields": [{ "name": "Playbook", "type": "choice", "options": ["Cutter", "Hound"] }]
+//!   },
+//!   "characters": [{
+//!     "name": "Vex", "pronouns": "she/her", "description": null, "image": null, "author_id": "80351110224678912",
+//!     "fields": { "Playbook": "Cutter" }
+//!   }],
+//!   "players": [{ "user_id": "80351110224678913", "character": "Vex" }],
+//!   "sessions": []
+//! }
+//! ```
+//!
+//! Changes that older bundles can't be read with must bump [`VERSION`].
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, GuildId, RoleId, UserId};
+use sqlx::{
+    query,
+    types::{
+        chrono::{DateTime, Utc},
+        Json,
+    },
+};
+
+use crate::{
+    sheet::{self, Field, Values},
+    Error, Result, DB,
+};
+
+/// The version of the bundle format written by this build.
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    /// The [`VERSION`] of the format this bundle was written in.
+    pub version: u32,
+    pub game: Game,
+    /// The system the game uses, matched by title when imported.
+    pub system: Option<System>,
+    pub characters: Vec<Character>,
+    pub players: Vec<Player>,
+    /// Reserved for session history, which isn't tracked yet. Always empty.
+    #[serde(default)]
+    pub sessions: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Game {
+    pub title: String,
+    pub abbreviation: String,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub owner_id: UserId,
+    pub role_id: RoleId,
+    pub main_channel_id: Option<ChannelId>,
+    #[serde(default)]
+    pub channels: Vec<Channel>,
+    pub nickname_template: Option<String>,
+    #[serde(default)]
+    pub open: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A channel linked to the game for a purpose, like OOC.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Channel {
+    pub channel_id: ChannelId,
+    pub purpose: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct System {
+    pub title: String,
+    pub abbreviation: String,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub link: Option<String>,
+    /// The custom fields characters in the system's games have.
+    #[serde(default)]
+    pub fields: Vec<Field>,
+    /// The [`Resolver::name`](crate::resolvers::Resolver::name) of how the system reads rolls.
+    #[serde(default)]
+    pub resolver: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Character {
+    /// Unique within the game.
+    pub name: String,
+    pub pronouns: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub author_id: UserId,
+    #[serde(default)]
+    pub fields: Values,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Player {
+    pub user_id: UserId,
+    /// The name of the player's character, if they have one.
+    pub character: Option<String>,
+}
+
+/// Collect a game in a server and everything in it into a bundle.
+pub async fn load(pool: &DB, guild_id: GuildId, game: i32) -> Result<Bundle> {
+    let game_data = query!(
+        r#"
+        select
+            title, abbreviation, description, image,
+            owner_id, role_id, main_channel_id,
+            nickname_template, open, created_at, system_id
+        from games
+        where id = $1 and guild_id = $2
+        "#,
+        game,
+        guild_id.get() as i64,
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or(Error::NotFound)?;
+
+    let channels = query!(
+        r#"
+        select channel_id, purpose
+        from game_channels
+        where game_id = $1
+        order by purpose
+        "#,
+        game,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let system = query!(
+        r#"
+        select
+            title, abbreviation, description, image, link,
+            fields as "fields: Json<Vec<Field>>",
+            resolver
+        from systems
+        where id = $1
+        "#,
+        game_data.system_id,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let characters = query!(
+        r#"
+        select
+            name, pronouns, description, image, author_id,
+            fields as "fields: Json<Values>"
+        from characters
+        where game_id = $1
+        order by name
+        "#,
+        game,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let players = query!(
+        r#"
+        select
+            user_id,
+            (select name from characters where id = character_id) as "character"
+        from players
+        where game_id = $1
+        order by user_id
+        "#,
+        game,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Bundle {
+        version: VERSION,
+        game: Game {
+            title: game_data.title,
+            abbreviation: game_data.abbreviation,
+            description: game_data.description,
+            image: game_data.image,
+            owner_id: UserId::from(game_data.owner_id as u64),
+            role_id: RoleId::from(game_data.role_id as u64),
+            main_channel_id: game_data.main_channel_id.map(|c| ChannelId::from(c as u64)),
+            channels: channels
+                .into_iter()
+                .map(|c| Channel {
+                    channel_id: ChannelId::from(c.channel_id as u64),
+                    purpose: c.purpose,
+                })
+                .collect(),
+            nickname_template: game_data.nickname_template,
+            open: game_data.open,
+            created_at: game_data.created_at,
+        },
+        system: system.map(|s| System {
+            title: s.title,
+            abbreviation: s.abbreviation,
+            description: s.description,
+            image: s.image,
+            link: s.link,
+            fields: s.fields.0,
+            resolver: s.resolver,
+        }),
+        characters: characters
+            .into_iter()
+            .map(|c| Character {
+                name: c.name,
+                pronouns: c.pronouns,
+                description: c.description,
+                image: c.image,
+                author_id: UserId::from(c.author_id as u64),
+                fields: c.fields.0,
+            })
+            .collect(),
+        players: players
+            .into_iter()
+            .map(|p| Player {
+                user_id: UserId::from(p.user_id as u64),
+                character: p.character,
+            })
+            .collect(),
+        sessions: vec![],
+    })
+}
+
+/// Find everything that would stop a bundle from being imported as it is.
+///
+/// `taken_titles` and `taken_abbreviations` are those of the games already in the server.
+pub fn conflicts(
+    bundle: &Bundle,
+    taken_titles: &HashSet<String>,
+    taken_abbreviations: &HashSet<String>,
+) -> Vec<String> {
+    let mut conflicts = vec![];
+
+    if bundle.version > VERSION {
+        conflicts.push(format!(
+            "This bundle is version {}, but I only understand up to version {VERSION}.",
+            bundle.version
+        ));
+    }
+
+    if taken_titles.contains(&bundle.game.title) {
+        conflicts.push(format!(
+            "A game called `{}` already exists. Choose a new `title`.",
+            bundle.game.title
+        ));
+    }
+
+    if taken_abbreviations.contains(&bundle.game.abbreviation) {
+        conflicts.push(format!(
+            "The abbreviation `{}` is already taken. Choose a new `abbreviation`.",
+            bundle.game.abbreviation
+        ));
+    }
+
+    let mut names = HashSet::new();
+    for character in &bundle.characters {
+        if !names.insert(&character.name) {
+            conflicts.push(format!(
+                "There's more than one character called `{}`.",
+                character.name
+            ));
+        }
+    }
+
+    let mut players = HashSet::new();
+    for player in &bundle.players {
+        if !players.insert(player.user_id) {
+            conflicts.push(format!(
+                "<@{}> is listed as a player more than once.",
+                player.user_id
+            ));
+        }
+        if let Some(character) = &player.character {
+            if !names.contains(character) {
+                conflicts.push(format!(
+                    "<@{}> plays `{character}`, but there's no character by that name.",
+                    player.user_id
+                ));
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Point a bundle at things that exist in the server it's being imported into,
+/// describing each change that was made.
+///
+/// The importer becomes the owner, and takes over characters whose authors aren't in the server.
+/// Players who aren't in the server, and channels that are missing or taken by other games, are left out.
+pub fn remap(
+    bundle: &mut Bundle,
+    importer: UserId,
+    members: &HashSet<UserId>,
+    available_channels: &HashSet<ChannelId>,
+) -> Vec<String> {
+    let mut changes = vec![];
+
+    if bundle.game.owner_id != importer {
+        changes.push(format!(
+            "You will own the game instead of <@{}>.",
+            bundle.game.owner_id
+        ));
+        bundle.game.owner_id = importer;
+    }
+
+    if let Some(channel_id) = bundle.game.main_channel_id {
+        if !available_channels.contains(&channel_id) {
+            changes.push(format!(
+                "The main channel <#{channel_id}> isn't available, so it was left out."
+            ));
+            bundle.game.main_channel_id = None;
+        }
+    }
+
+    bundle.game.channels.retain(|channel| {
+        let available = available_channels.contains(&channel.channel_id);
+        if !available {
+            changes.push(format!(
+                "The {} channel <#{}> isn't available, so it was left out.",
+                channel.purpose, channel.channel_id
+            ));
+        }
+        available
+    });
+
+    for character in &mut bundle.characters {
+        if !members.contains(&character.author_id) {
+            changes.push(format!(
+                "You will be the author of `{}`, since <@{}> isn't in this server.",
+                character.name, character.author_id
+            ));
+            character.author_id = importer;
+        }
+    }
+
+    bundle.players.retain(|player| {
+        if player.user_id == importer {
+            changes.push("You own the game, so you won't also be a player.".to_string());
+            false
+        } else if !members.contains(&player.user_id) {
+            changes.push(format!(
+                "<@{}> isn't in this server, so they won't be added as a player.",
+                player.user_id
+            ));
+            false
+        } else {
+            true
+        }
+    });
+
+    changes
+}
+
+/// Write a bundle as a campaign summary that people can read.
+pub fn markdown(bundle: &Bundle) -> String {
+    let game = &bundle.game;
+    let mut out = format!("# {} [{}]\n\n", game.title, game.abbreviation);
+
+    if let Some(system) = &bundle.system {
+        out.push_str(&format!(
+            "**System:** {} [{}]\n",
+            system.title, system.abbreviation
+        ));
+    }
+    out.push_str(&format!(
+        "**Created:** {}\n",
+        game.created_at.format("%Y-%m-%d")
+    ));
+
+    if let Some(description) = &game.description {
+        out.push_str(&format!("\n{description}\n"));
+    }
+
+    out.push_str("\n## Players\n\n");
+    if bundle.players.is_empty() {
+        out.push_str("No players.\n");
+    }
+    for player in &bundle.players {
+        match &player.character {
+            Some(character) => out.push_str(&format!("- <@{}> as {character}\n", player.user_id)),
+            None => out.push_str(&format!("- <@{}>\n", player.user_id)),
+        }
+    }
+
+    let schema = bundle.system.as_ref().map_or(&[][..], |s| &s.fields);
+    out.push_str("\n## Characters\n");
+    if bundle.characters.is_empty() {
+        out.push_str("\nNo characters.\n");
+    }
+    for character in &bundle.characters {
+        out.push_str(&format!("\n### {}", character.name));
+        if let Some(pronouns) = &character.pronouns {
+            out.push_str(&format!(" ({pronouns})"));
+        }
+        out.push('\n');
+        let rendered = sheet::render(schema, &character.fields);
+        if !rendered.is_empty() {
+            out.push('\n');
+        }
+        for (name, value) in rendered {
+            out.push_str(&format!("- **{name}:** {value}\n"));
+        }
+        if let Some(description) = &character.description {
+            out.push_str(&format!("\n{description}\n"));
+        }
+    }
+
+    out
+}
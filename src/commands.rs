@@ -1,18 +1,47 @@
 use std::{future::Future, time::Duration};
 
+use poise::CreateReply;
 use serenity::all::{
-    CreateInteractionResponse, CreateInteractionResponseMessage, CreateQuickModal,
+    ButtonStyle, Channel, ChannelType, ComponentInteraction, ComponentInteractionCollector,
+    CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateQuickModal,
 };
 use sqlx::query;
 
-use crate::{Context, Error, Result};
+use crate::{Context, Data, Error, Result};
 
 pub mod character;
 pub mod game;
+pub mod roll;
+pub mod settings;
 pub mod system;
 
 pub fn all() -> Vec<crate::Command> {
-    vec![system::system(), game::game(), character::character()]
+    vec![
+        system::system(),
+        game::game(),
+        character::character(),
+        roll::roll(),
+        settings::settings(),
+    ]
+}
+
+/// Handle a press of a button that has to keep working after the command that sent it has finished.
+///
+/// Their custom ids look like `transfer:accept:12`: what the button belongs to, what it does, and which row it's about.
+/// Buttons with any other id are handled by the command that sent them.
+pub async fn component(
+    ctx: &serenity::all::Context,
+    data: &Data,
+    press: &ComponentInteraction,
+) -> Result<()> {
+    let mut parts = press.data.custom_id.splitn(3, ':');
+    match (parts.next(), parts.next(), parts.next().map(str::parse)) {
+        (Some(kind), Some(action), Some(Ok(id))) => {
+            game::component(ctx, data, press, kind, action, id).await
+        }
+        _ => Ok(()),
+    }
 }
 
 #[bon::builder]
@@ -70,12 +99,202 @@ where
     }
 }
 
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Send a reply with confirm and cancel buttons, and wait for the author to press one.
+/// Returns whether the author confirmed.
+#[bon::builder]
+pub async fn confirmation_buttons(
+    ctx: &Context<'_>,
+    reply: CreateReply,
+    confirm_label: Option<&str>,
+) -> Result<bool> {
+    let ctx_id = ctx.id();
+    let confirm_button_id = format!("{ctx_id}confirm");
+    let cancel_button_id = format!("{ctx_id}cancel");
+
+    let handle = ctx
+        .send(reply.clone().components(vec![CreateActionRow::Buttons(vec![
+            CreateButton::new(&confirm_button_id)
+                .label(confirm_label.unwrap_or("Confirm"))
+                .style(ButtonStyle::Success),
+            CreateButton::new(&cancel_button_id)
+                .label("Cancel")
+                .style(ButtonStyle::Secondary),
+        ])]))
+        .await?;
+
+    let press = ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(CONFIRMATION_TIMEOUT)
+        .await;
+
+    match press {
+        Some(press) => {
+            press
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::UpdateMessage(
+                        CreateInteractionResponseMessage::new().components(vec![]),
+                    ),
+                )
+                .await?;
+            Ok(press.data.custom_id == confirm_button_id)
+        }
+        None => {
+            handle
+                .edit(poise::Context::Application(*ctx), reply.components(vec![]))
+                .await?;
+            Ok(false)
+        }
+    }
+}
+
+const PAGINATE_TIMEOUT: Duration = Duration::from_secs(600);
+
+fn page_embed(title: &str, pages: &[String], page: usize) -> CreateEmbed {
+    CreateEmbed::new()
+        .title(title)
+        .description(&pages[page])
+        .footer(CreateEmbedFooter::new(format!(
+            "Page {} of {}",
+            page + 1,
+            pages.len()
+        )))
+}
+
+/// Show a list of lines as an embed, split into pages with navigation buttons.
+#[bon::builder]
+pub async fn paginate(
+    ctx: &Context<'_>,
+    title: &str,
+    lines: Vec<String>,
+    per_page: Option<usize>,
+    empty_message: Option<&str>,
+) -> Result<()> {
+    if lines.is_empty() {
+        ctx.say(empty_message.unwrap_or("Nothing to show!")).await?;
+        return Ok(());
+    }
+
+    let pages: Vec<String> = lines
+        .chunks(per_page.unwrap_or(10))
+        .map(|chunk| chunk.join("\n"))
+        .collect();
+
+    let ctx_id = ctx.id();
+    let prev_button_id = format!("{ctx_id}prev");
+    let next_button_id = format!("{ctx_id}next");
+
+    let buttons = |disabled: bool| {
+        vec![CreateActionRow::Buttons(vec![
+            CreateButton::new(&prev_button_id)
+                .emoji('◀')
+                .disabled(disabled),
+            CreateButton::new(&next_button_id)
+                .emoji('▶')
+                .disabled(disabled),
+        ])]
+    };
+
+    let mut reply = CreateReply::default().embed(page_embed(title, &pages, 0));
+    if pages.len() > 1 {
+        reply = reply.components(buttons(false));
+    }
+    let handle = ctx.send(reply).await?;
+
+    if pages.len() == 1 {
+        return Ok(());
+    }
+
+    let mut page = 0;
+    while let Some(press) = ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .timeout(PAGINATE_TIMEOUT)
+        .await
+    {
+        if press.data.custom_id == next_button_id {
+            page = (page + 1) % pages.len();
+        } else if press.data.custom_id == prev_button_id {
+            page = page.checked_sub(1).unwrap_or(pages.len() - 1);
+        } else {
+            continue;
+        }
+
+        press
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new().embed(page_embed(title, &pages, page)),
+                ),
+            )
+            .await?;
+    }
+
+    handle
+        .edit(
+            poise::Context::Application(*ctx),
+            CreateReply::default()
+                .embed(page_embed(title, &pages, page))
+                .components(buttons(true)),
+        )
+        .await?;
+
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct ContextualArgs {
     pub game_id: i32,
     pub character_id: Option<i32>,
 }
 
+/// Find the game linked to the channel a command was run in.
+/// Threads count as part of the channel they were created in.
+pub async fn channel_game(ctx: &Context<'_>) -> Result<Option<i32>> {
+    let channel_id = match ctx.channel_id().to_channel(ctx).await? {
+        Channel::Guild(channel)
+            if matches!(
+                channel.kind,
+                ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread
+            ) =>
+        {
+            channel.parent_id.unwrap_or(channel.id)
+        }
+        channel => channel.id(),
+    };
+
+    let maybe_game_data = query!(
+        r#"
+        select id
+        from games as g
+        where
+            guild_id = $1
+            and
+            (
+                main_channel_id = $2
+                or
+                exists (
+                    select 1
+                    from game_channels
+                    where
+                        game_id = g.id
+                        and
+                        channel_id = $2
+                )
+            )
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        channel_id.get() as i64,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?;
+
+    Ok(maybe_game_data.map(|game_data| game_data.id))
+}
+
 #[bon::builder]
 pub async fn contextual_args(
     ctx: &Context<'_>,
@@ -84,23 +303,7 @@ pub async fn contextual_args(
 ) -> Result<ContextualArgs> {
     let maybe_game_id: Option<i32> = match game_id_arg {
         Some(Some(game_id)) => Some(game_id),
-        None | Some(None) => {
-            let maybe_game_data = query!(
-                r#"
-                select id
-                from games
-                where
-                    guild_id = $1
-                    and
-                    main_channel_id = $2
-                "#,
-                ctx.guild_id().unwrap().get() as i64,
-                ctx.channel_id().get() as i64,
-            )
-            .fetch_optional(&ctx.data().pool)
-            .await?;
-            maybe_game_data.map(|game_data| game_data.id)
-        }
+        None | Some(None) => channel_game(ctx).await?,
     };
 
     let game_id = match maybe_game_id {
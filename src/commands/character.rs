@@ -1,16 +1,21 @@
-use crate::{Context, Error, Result};
+use crate::{
+    sheet::{self, Field, Values},
+    Context, Error, Result, DB,
+};
 
 mod assign;
 mod claim;
 mod create;
 mod delete;
 mod edit;
+mod fields;
+mod import;
 mod release;
 mod view;
 
 use poise::Modal;
 use serenity::all::{CreateEmbed, CreateEmbedFooter, Member};
-use sqlx::query;
+use sqlx::{query, types::Json};
 
 #[poise::command(
     slash_command,
@@ -19,10 +24,12 @@ use sqlx::query;
         "create::create",
         "view::view",
         "edit::edit",
+        "fields::fields",
         "delete::delete",
         "claim::claim",
         "release::release",
         "assign::assign",
+        "import::import",
     ),
     guild_only
 )]
@@ -60,6 +67,7 @@ pub fn character_embed(
     image: RequiredStringOption,
     game: String,
     player: RequiredMemberOption,
+    #[builder(default)] fields: Vec<(String, String)>,
 ) -> CreateEmbed {
     let mut embed = CreateEmbed::new().title(name);
 
@@ -69,6 +77,10 @@ pub fn character_embed(
 
     embed = embed.field("Game", game, true);
 
+    for (name, value) in fields {
+        embed = embed.field(name, value, true);
+    }
+
     if let Some(description) = description {
         embed = embed.field("Description", description, false);
     }
@@ -88,6 +100,32 @@ pub fn character_embed(
     embed
 }
 
+/// The fields a character's system defines, and the character's values for them.
+pub async fn field_values(pool: &DB, character: i32) -> Result<(Vec<Field>, Values)> {
+    let record = query!(
+        r#"
+        select
+            c.fields as "values: Json<Values>",
+            coalesce(s.fields, '[]') as "fields!: Json<Vec<Field>>"
+        from characters as c
+        join games as g on g.id = c.game_id
+        left join systems as s on s.id = g.system_id
+        where c.id = $1
+        "#,
+        character,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok((record.fields.0, record.values.0))
+}
+
+/// [`field_values`], ready to show in [`character_embed`].
+pub async fn rendered_fields(pool: &DB, character: i32) -> Result<Vec<(String, String)>> {
+    let (fields, values) = field_values(pool, character).await?;
+    Ok(sheet::render(&fields, &values))
+}
+
 pub async fn can_manage(ctx: Context<'_>, character: i32) -> Result<()> {
     if ctx
         .author_member()
@@ -4,7 +4,7 @@ use sqlx::query;
 
 use crate::{
     commands::{contextual_args, game::can_manage},
-    Context, Result,
+    nickname, Context, Result,
 };
 
 /// Assign a character to a player. Usable by game owners and server moderators.
@@ -45,12 +45,13 @@ pub async fn assign(
 
     match maybe_record {
         Ok(record) => {
-            ctx.say(format!(
-                "`{}` assigned to {}.",
-                record.name.unwrap(),
-                user.mention()
-            ))
-            .await?;
+            let mut content = format!("`{}` assigned to {}.", record.name.unwrap(), user.mention());
+            let refreshed =
+                nickname::refresh(ctx, &ctx.data().pool, ctx_args.game_id, user.user.id).await;
+            if let Some(note) = nickname::describe(&refreshed) {
+                content = format!("{content}\n{note}");
+            }
+            ctx.say(content).await?;
             Ok(())
         }
         Err(sqlx::Error::RowNotFound) => {
@@ -3,7 +3,7 @@ use sqlx::query;
 
 use crate::{
     commands::{character::is_in_game, contextual_args},
-    Context, Result,
+    nickname, Context, Result,
 };
 
 /// Claim a character that currently has no player. Usable by all players.
@@ -42,12 +42,17 @@ pub async fn claim(
     .fetch_one(&ctx.data().pool)
     .await?;
 
-    ctx.say(format!(
+    let mut content = format!(
         "`{}` claimed by {}.",
         record.name.unwrap(),
         ctx.author_member().await.unwrap().mention()
-    ))
-    .await?;
+    );
+    let refreshed =
+        nickname::refresh(ctx, &ctx.data().pool, ctx_args.game_id, ctx.author().id).await;
+    if let Some(note) = nickname::describe(&refreshed) {
+        content = format!("{content}\n{note}");
+    }
+    ctx.say(content).await?;
 
     Ok(())
 }
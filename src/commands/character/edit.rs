@@ -3,7 +3,7 @@ use sqlx::query;
 
 use crate::{
     commands::{
-        character::{can_manage, character_embed, CharacterModal},
+        character::{can_manage, character_embed, rendered_fields, CharacterModal},
         contextual_args,
     },
     Context, Result,
@@ -91,6 +91,8 @@ pub async fn edit(
             _ => None,
         };
 
+        let fields = rendered_fields(&ctx.data().pool, character).await?;
+
         ctx.send(
             CreateReply::default().content("Character updated!").embed(
                 character_embed()
@@ -100,6 +102,7 @@ pub async fn edit(
                     .image(character_data.image)
                     .game(record.game.unwrap())
                     .player(player)
+                    .fields(fields)
                     .call(),
             ),
         )
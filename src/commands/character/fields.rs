@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+use serenity::all::{
+    ButtonStyle, ComponentInteractionCollector, CreateActionRow, CreateButton, CreateInputText,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateQuickModal, InputTextStyle,
+    ModalInteraction,
+};
+use sqlx::{query, types::Json};
+
+use crate::{
+    commands::{
+        character::{can_manage, character_embed, field_values},
+        contextual_args,
+    },
+    sheet::{self, Field, Kind},
+    Context, Error, Result,
+};
+
+const MODAL_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Modals can only hold this many inputs, so longer schemas are filled in over several steps.
+const FIELDS_PER_MODAL: usize = 5;
+
+fn input(field: &Field, value: Option<String>) -> CreateInputText {
+    let style = match field.kind {
+        Kind::Text => InputTextStyle::Paragraph,
+        _ => InputTextStyle::Short,
+    };
+    let placeholder: String = field.kind.describe().chars().take(100).collect();
+
+    let mut input = CreateInputText::new(style, &field.name, "")
+        .placeholder(placeholder)
+        .max_length(sheet::MAX_TEXT_LENGTH as u16)
+        .required(false);
+    if let Some(value) = value {
+        input = input.value(value);
+    }
+    input
+}
+
+/// Fill in custom fields. Usable by a character's player/author, server moderators, and game owners.
+#[poise::command(slash_command)]
+pub async fn fields(
+    ctx: Context<'_>,
+    #[description = "The character to fill in"]
+    #[autocomplete = "crate::autocomplete::character_editable"]
+    character: Option<i32>,
+) -> Result<()> {
+    let character = contextual_args()
+        .character_id_arg(character)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .character_id
+        .unwrap();
+
+    can_manage(ctx, character).await?;
+
+    let (fields, mut values) = field_values(&ctx.data().pool, character).await?;
+
+    if fields.is_empty() {
+        return Err(Error::Message(
+            "This character's system doesn't have any fields. Server moderators can add some with `/system fields`."
+                .to_string(),
+        ));
+    }
+
+    let steps: Vec<&[Field]> = fields.chunks(FIELDS_PER_MODAL).collect();
+    let next_button_id = format!("{}next", ctx.id());
+    let mut problems = vec![];
+    let mut submitted: Option<ModalInteraction> = None;
+
+    for (step, step_fields) in steps.iter().enumerate() {
+        let modal = step_fields.iter().fold(
+            CreateQuickModal::new(format!("Fields ({}/{})", step + 1, steps.len()))
+                .timeout(MODAL_TIMEOUT),
+            |modal, field| modal.field(input(field, values.get(&field.name).map(sheet::display))),
+        );
+
+        let response = match submitted.take() {
+            None => {
+                ctx.interaction
+                    .quick_modal(ctx.serenity_context, modal)
+                    .await?
+            }
+            // A modal can't open another modal, so ask for a button press in between.
+            Some(previous) => {
+                previous
+                    .create_response(
+                        ctx,
+                        CreateInteractionResponse::Message(
+                            CreateInteractionResponseMessage::new()
+                                .content(format!("Saved step {step} of {}.", steps.len()))
+                                .components(vec![CreateActionRow::Buttons(vec![
+                                    CreateButton::new(&next_button_id)
+                                        .label("Next")
+                                        .style(ButtonStyle::Primary),
+                                ])])
+                                .ephemeral(true),
+                        ),
+                    )
+                    .await?;
+
+                let next_button_id = next_button_id.clone();
+                let Some(press) = ComponentInteractionCollector::new(ctx)
+                    .author_id(ctx.author().id)
+                    .filter(move |press| press.data.custom_id == next_button_id)
+                    .timeout(MODAL_TIMEOUT)
+                    .await
+                else {
+                    return Ok(());
+                };
+
+                press.quick_modal(ctx.serenity_context, modal).await?
+            }
+        };
+
+        let Some(response) = response else {
+            return Ok(());
+        };
+
+        for (field, value) in step_fields.iter().zip(&response.inputs) {
+            match sheet::validate(field, value) {
+                Ok(Some(value)) => {
+                    values.insert(field.name.clone(), value);
+                }
+                Ok(None) => {
+                    values.remove(&field.name);
+                }
+                Err(problem) => problems.push(problem),
+            }
+        }
+
+        query!(
+            r#"
+            update characters
+            set fields = $2
+            where id = $1
+            "#,
+            character,
+            Json(&values) as _,
+        )
+        .execute(&ctx.data().pool)
+        .await?;
+
+        submitted = Some(response.interaction);
+    }
+
+    let record = query!(
+        r#"
+        select
+            name, pronouns, description, image,
+            (select title from games where id = game_id) as "game",
+            (select user_id from players where character_id = $1) as "player"
+        from characters
+        where id = $1
+        "#,
+        character,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?;
+
+    let player = match record.player {
+        Some(player_id) => Some(
+            ctx.guild_id()
+                .unwrap()
+                .member(ctx, player_id as u64)
+                .await?,
+        ),
+        _ => None,
+    };
+
+    let mut content = "Fields updated!".to_string();
+    if !problems.is_empty() {
+        content = format!(
+            "{content}\nThese were left as they were:\n{}",
+            problems.join("\n")
+        );
+    }
+
+    submitted
+        .unwrap()
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .embed(
+                        character_embed()
+                            .name(record.name)
+                            .pronouns(record.pronouns)
+                            .description(record.description)
+                            .image(record.image)
+                            .game(record.game.unwrap())
+                            .player(player)
+                            .fields(sheet::render(&fields, &values))
+                            .call(),
+                    ),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
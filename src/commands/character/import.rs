@@ -0,0 +1,239 @@
+use std::collections::{HashMap, HashSet};
+
+use serenity::all::{Attachment, Mentionable, UserId};
+use sqlx::query;
+
+use crate::{
+    commands::{contextual_args, game::can_manage, paginate},
+    nickname,
+    spreadsheet::{self, Row},
+    sync, Context, Error, Result,
+};
+
+/// Create characters from a CSV file. Usable by game owners and server moderators.
+#[poise::command(slash_command)]
+pub async fn import(
+    ctx: Context<'_>,
+    #[description = "A CSV with the columns name, pronouns, description, image and player"]
+    file: Attachment,
+    #[description = "The game to add the characters to"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    ctx.defer().await?;
+
+    let text = String::from_utf8(file.download().await?)
+        .map_err(|_| Error::Message("That file isn't a text CSV.".to_string()))?;
+
+    let (rows, mut problems) = spreadsheet::parse(&text);
+
+    let game_data = query!(
+        r#"
+        select
+            title,
+            array(select name from characters where game_id = g.id) as "characters!",
+            array(select user_id from players where game_id = g.id) as "players!",
+            array(
+                select user_id
+                from players
+                where game_id = g.id and character_id is not null
+            ) as "playing!"
+        from games as g
+        where id = $1 and guild_id = $2
+        "#,
+        game,
+        ctx.guild_id().unwrap().get() as i64,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?
+    .ok_or(Error::NotFound)?;
+
+    for row in &rows {
+        if game_data.characters.contains(&row.name) {
+            problems.push(format!(
+                "Row {}: `{}` already exists in this game.",
+                row.number, row.name
+            ));
+        }
+    }
+
+    let assignments = assignments()
+        .ctx(ctx)
+        .rows(&rows)
+        .players(&game_data.players)
+        .playing(&game_data.playing)
+        .problems(&mut problems)
+        .call()
+        .await?;
+
+    if !problems.is_empty() {
+        paginate()
+            .ctx(&ctx)
+            .title(&format!("Nothing was imported into {}", game_data.title))
+            .lines(problems)
+            .call()
+            .await?;
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        return Err(Error::Message(
+            "There are no characters in that file.".to_string(),
+        ));
+    }
+
+    let mut tx = ctx.data().pool.begin().await?;
+
+    for row in &rows {
+        let id = query!(
+            r#"
+            insert
+            into characters
+                (
+                    guild_id, author_id, game_id,
+                    name, pronouns, description, image
+                )
+            values
+                ($1, $2, $3, $4, $5, $6, $7)
+            returning id
+            "#,
+            ctx.guild_id().unwrap().get() as i64,
+            ctx.author().id.get() as i64,
+            game,
+            row.name,
+            row.pronouns,
+            row.description,
+            row.image,
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .id;
+
+        if let Some(user_id) = assignments.get(&row.number) {
+            query!(
+                r#"
+                update players
+                set character_id = $3
+                where game_id = $1 and user_id = $2
+                "#,
+                game,
+                user_id.get() as i64,
+                id,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    let mut lines = vec![];
+    for row in &rows {
+        match assignments.get(&row.number) {
+            Some(&user_id) => {
+                let mut line = format!("✅ `{}` assigned to {}", row.name, user_id.mention());
+                let refreshed = nickname::refresh(ctx, &ctx.data().pool, game, user_id).await;
+                if let Some(note) = nickname::describe(&refreshed) {
+                    line = format!("{line}\n-# {note}");
+                }
+                lines.push(line);
+            }
+            None => lines.push(format!("✅ `{}`", row.name)),
+        }
+    }
+
+    paginate()
+        .ctx(&ctx)
+        .title(&format!("Characters imported into {}", game_data.title))
+        .lines(lines)
+        .call()
+        .await?;
+
+    Ok(())
+}
+
+/// Work out which player each row's character goes to, by row number.
+///
+/// Players can be written as a mention, an id, or a name, and must already be in the game
+/// without a character.
+#[bon::builder]
+async fn assignments(
+    ctx: Context<'_>,
+    rows: &[Row],
+    players: &[i64],
+    /// Players who already have a character.
+    playing: &[i64],
+    problems: &mut Vec<String>,
+) -> Result<HashMap<usize, UserId>> {
+    let mut assignments = HashMap::new();
+
+    if rows.iter().all(|row| row.player.is_none()) {
+        return Ok(assignments);
+    }
+
+    let members = sync::guild_members(ctx.http(), ctx.guild_id().unwrap()).await?;
+    let mut assigned = HashSet::new();
+
+    for row in rows {
+        let Some(player) = &row.player else {
+            continue;
+        };
+
+        let user_id = spreadsheet::user_id(player).or_else(|| {
+            let matching: Vec<UserId> = members
+                .iter()
+                .filter(|m| {
+                    [
+                        Some(m.user.name.as_str()),
+                        m.user.global_name.as_deref(),
+                        m.nick.as_deref(),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .any(|name| name.eq_ignore_ascii_case(player))
+                })
+                .map(|m| m.user.id)
+                .collect();
+            match matching[..] {
+                [user_id] => Some(user_id),
+                _ => None,
+            }
+        });
+
+        match user_id {
+            None => problems.push(format!(
+                "Row {}: I couldn't find exactly one member called `{player}`.",
+                row.number
+            )),
+            Some(user_id) if !players.contains(&(user_id.get() as i64)) => problems.push(format!(
+                "Row {}: {} isn't a player in this game.",
+                row.number,
+                user_id.mention()
+            )),
+            Some(user_id) if playing.contains(&(user_id.get() as i64)) => problems.push(format!(
+                "Row {}: {} already plays a character in this game. Release it first with `/character release`.",
+                row.number,
+                user_id.mention()
+            )),
+            Some(user_id) if !assigned.insert(user_id) => problems.push(format!(
+                "Row {}: {} is already given another character in this file.",
+                row.number,
+                user_id.mention()
+            )),
+            Some(user_id) => {
+                assignments.insert(row.number, user_id);
+            }
+        }
+    }
+
+    Ok(assignments)
+}
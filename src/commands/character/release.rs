@@ -1,8 +1,9 @@
+use serenity::all::UserId;
 use sqlx::query;
 
 use crate::{
     commands::{character::can_manage, contextual_args},
-    Context, Result,
+    nickname, Context, Result,
 };
 
 /// Unassign a character. Usable by a character's player/author, server moderators, and game owners.
@@ -28,15 +29,28 @@ pub async fn release(
         update players
         set character_id = null
         where character_id = $1
-        returning (select name from characters where id = $1)
+        returning
+            user_id,
+            game_id,
+            (select name from characters where id = $1)
         "#,
         character
     )
     .fetch_one(&ctx.data().pool)
     .await?;
 
-    ctx.say(format!("`{}` released.", record.name.unwrap()))
-        .await?;
+    let mut content = format!("`{}` released.", record.name.unwrap());
+    let refreshed = nickname::refresh(
+        ctx,
+        &ctx.data().pool,
+        record.game_id,
+        UserId::from(record.user_id as u64),
+    )
+    .await;
+    if let Some(note) = nickname::describe(&refreshed) {
+        content = format!("{content}\n{note}");
+    }
+    ctx.say(content).await?;
 
     Ok(())
 }
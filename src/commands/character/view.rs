@@ -2,7 +2,10 @@ use poise::CreateReply;
 use sqlx::query;
 
 use crate::{
-    commands::{character::character_embed, contextual_args},
+    commands::{
+        character::{character_embed, rendered_fields},
+        contextual_args,
+    },
     Context, Result,
 };
 
@@ -14,7 +17,7 @@ pub async fn view(
     #[autocomplete = "crate::autocomplete::character"]
     character: Option<i32>,
 ) -> Result<()> {
-    let character = contextual_args()
+    let character_id = contextual_args()
         .character_id_arg(character)
         .ctx(&ctx)
         .call()
@@ -31,7 +34,7 @@ pub async fn view(
         from characters
         where id = $1 and guild_id = $2
         "#,
-        character,
+        character_id,
         ctx.guild_id().unwrap().get() as i64,
     )
     .fetch_optional(&ctx.data().pool)
@@ -43,6 +46,7 @@ pub async fn view(
                 Some(player) => Some(ctx.guild_id().unwrap().member(ctx, player as u64).await?),
                 _ => None,
             };
+            let fields = rendered_fields(&ctx.data().pool, character_id).await?;
             ctx.send(
                 CreateReply::default().embed(
                     character_embed()
@@ -52,6 +56,7 @@ pub async fn view(
                         .image(character.image)
                         .game(character.game.unwrap())
                         .player(player)
+                        .fields(fields)
                         .call(),
                 ),
             )
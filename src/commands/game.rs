@@ -1,24 +1,43 @@
-use crate::{Context, Error, Result};
-
 mod activate;
+mod announce;
 mod channel;
+mod clone;
 mod create;
 mod deactivate;
 mod delete;
 mod edit;
+mod export;
+mod import;
+mod join;
+mod leave;
+mod list;
+mod nickname;
+mod open;
+mod role;
+mod rolls;
+mod sync;
+mod template;
 mod transfer;
 mod view;
 
 mod player;
 mod system;
 
-use poise::Modal;
-use serenity::all::{ChannelId, CreateEmbed, CreateEmbedFooter, Mentionable, RoleId, UserId};
+use std::time::Duration;
+
+use poise::{CreateReply, Modal, ReplyHandle};
+use serenity::all::{
+    ButtonStyle, ChannelId, Colour, ComponentInteraction, ComponentInteractionCollector,
+    CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+    EditRole, Mentionable, RoleId, UserId,
+};
 use sqlx::{
     query,
     types::chrono::{DateTime, Utc},
 };
 
+use crate::{commands::confirmation_buttons, nickname::Outcome, Context, Data, Error, Result};
+
 #[poise::command(
     slash_command,
     subcommand_required,
@@ -26,13 +45,26 @@ use sqlx::{
         "player::player",
         "system::system",
         "channel::channel",
+        "announce::announce",
         "create::create",
+        "clone::clone",
+        "template::template",
         "view::view",
+        "list::list",
+        "join::join",
+        "leave::leave",
+        "open::open",
         "edit::edit",
         "delete::delete",
+        "export::export",
+        "import::import",
         "transfer::transfer",
         "activate::activate",
         "deactivate::deactivate",
+        "nickname::nickname",
+        "role::role",
+        "rolls::rolls",
+        "sync::sync",
     ),
     guild_only
 )]
@@ -40,7 +72,24 @@ pub async fn game(_: Context<'_>) -> Result<()> {
     Ok(())
 }
 
-pub async fn can_manage(ctx: Context<'_>, game: i32) -> Result<()> {
+/// Handle a press of a button sent by one of the game commands. See [`crate::commands::component`].
+pub async fn component(
+    ctx: &serenity::all::Context,
+    data: &Data,
+    press: &ComponentInteraction,
+    kind: &str,
+    action: &str,
+    id: i32,
+) -> Result<()> {
+    match kind {
+        "transfer" => transfer::respond(ctx, data, press, action, id).await,
+        "invite" => player::respond_to_invite(ctx, data, press, action, id).await,
+        _ => Ok(()),
+    }
+}
+
+/// Whether the author owns this game or is a server moderator.
+pub async fn is_manager(ctx: Context<'_>, game: i32) -> Result<bool> {
     if ctx
         .author_member()
         .await
@@ -49,7 +98,7 @@ pub async fn can_manage(ctx: Context<'_>, game: i32) -> Result<()> {
         .unwrap()
         .manage_messages()
     {
-        return Ok(());
+        return Ok(true);
     }
 
     let record = query!(
@@ -66,7 +115,11 @@ pub async fn can_manage(ctx: Context<'_>, game: i32) -> Result<()> {
     .fetch_one(&ctx.data().pool)
     .await?;
 
-    if record.exists.unwrap() {
+    Ok(record.exists.unwrap())
+}
+
+pub async fn can_manage(ctx: Context<'_>, game: i32) -> Result<()> {
+    if is_manager(ctx, game).await? {
         Ok(())
     } else {
         Err(Error::Message(
@@ -75,6 +128,176 @@ pub async fn can_manage(ctx: Context<'_>, game: i32) -> Result<()> {
     }
 }
 
+/// Make sure a new game's title, abbreviation, and main channel aren't already taken in this server.
+pub async fn check_available(
+    ctx: Context<'_>,
+    title: &str,
+    abbreviation: &str,
+    channel: Option<ChannelId>,
+) -> Result<()> {
+    let maybe_already_exists = query!(
+        r#"
+        select
+            title, abbreviation, main_channel_id
+        from games
+        where
+            guild_id = $3
+            and
+            (
+                title = $1
+                or
+                abbreviation = $2
+                or
+                main_channel_id = $4
+            )
+        "#,
+        title,
+        abbreviation,
+        ctx.guild_id().unwrap().get() as i64,
+        channel.map(|c| c.get() as i64),
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?;
+
+    match maybe_already_exists {
+        Some(already_exists) if already_exists.title == title => {
+            Err(Error::Message(format!("`{title}` already exists!")))
+        }
+        Some(already_exists) if already_exists.abbreviation == abbreviation => Err(Error::Message(
+            format!("`{abbreviation}` is already taken!"),
+        )),
+        Some(_) => Err(Error::Message(format!(
+            "{} is already taken!",
+            channel.unwrap().mention()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// A game that was just created by [`insert_game`].
+pub struct NewGame {
+    pub id: i32,
+    pub role_id: RoleId,
+    pub created_at: DateTime<Utc>,
+    pub system: Option<String>,
+}
+
+/// Create a game owned by the author, along with its role.
+#[bon::builder]
+pub async fn insert_game(
+    ctx: &Context<'_>,
+    title: &str,
+    abbreviation: &str,
+    description: Option<&str>,
+    image: Option<&str>,
+    system: Option<i32>,
+    channel: Option<ChannelId>,
+    nickname_template: Option<&str>,
+) -> Result<NewGame> {
+    let role = ctx
+        .guild_id()
+        .unwrap()
+        .create_role(
+            *ctx,
+            EditRole::new()
+                .name(abbreviation)
+                .audit_log_reason("Game role created")
+                .mentionable(true),
+        )
+        .await?;
+
+    let inserted = query!(
+        r#"
+        insert
+        into games
+            (
+                title, abbreviation, description, image,
+                guild_id, owner_id, role_id, system_id,
+                main_channel_id, nickname_template
+            )
+        values
+            ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        returning
+            id,
+            created_at,
+            (select abbreviation from systems where id = $8) as "system"
+        "#,
+        title,
+        abbreviation,
+        description,
+        image,
+        ctx.guild_id().unwrap().get() as i64,
+        ctx.author().id.get() as i64,
+        role.id.get() as i64,
+        system,
+        channel.map(|c| c.get() as i64),
+        nickname_template,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await;
+
+    let inserted = match inserted {
+        Ok(inserted) => inserted,
+        Err(e) => {
+            // Don't leave a role behind for a game that doesn't exist.
+            ctx.guild_id().unwrap().delete_role(*ctx, role.id).await?;
+            return Err(e.into());
+        }
+    };
+
+    ctx.author_member()
+        .await
+        .unwrap()
+        .add_role(*ctx, role.id)
+        .await?;
+
+    Ok(NewGame {
+        id: inserted.id,
+        role_id: role.id,
+        created_at: inserted.created_at,
+        system: inserted.system,
+    })
+}
+
+/// Parse a role colour written in hex, like `#e91e63`.
+pub fn parse_colour(value: &str) -> Option<Colour> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok().map(Colour::new)
+}
+
+/// Edit a game's role, offering to retry until it works or the author gives up.
+/// Returns whether the role was edited.
+pub async fn edit_role(ctx: Context<'_>, role_id: RoleId, role: EditRole<'_>) -> Result<bool> {
+    loop {
+        let error = match ctx
+            .guild_id()
+            .unwrap()
+            .edit_role(ctx, role_id, role.clone())
+            .await
+        {
+            Ok(_) => return Ok(true),
+            Err(e) => e,
+        };
+
+        let retry = confirmation_buttons()
+            .ctx(&ctx)
+            .reply(
+                CreateReply::default()
+                    .content(format!("I couldn't update {}: {error}", role_id.mention())),
+            )
+            .confirm_label("Retry")
+            .call()
+            .await?;
+
+        if !retry {
+            return Ok(false);
+        }
+    }
+}
+
 #[derive(Debug, Default, Modal)]
 #[name = "Game Details"]
 pub struct GameModal {
@@ -97,6 +320,7 @@ pub struct GameModal {
 
 type RequiredStringOption = Option<String>;
 type RequiredChannelOption = Option<ChannelId>;
+type RequiredDateTimeOption = Option<DateTime<Utc>>;
 
 #[bon::builder]
 pub fn game_embed(
@@ -106,10 +330,14 @@ pub fn game_embed(
     image: RequiredStringOption,
     system: RequiredStringOption,
     created_at: DateTime<Utc>,
+    active_since: RequiredDateTimeOption,
+    #[builder(default)] open: bool,
     role_id: RoleId,
     channel_id: RequiredChannelOption,
     owner_id: UserId,
     players: Vec<UserId>,
+    #[builder(default)] channels: Vec<(ChannelId, String)>,
+    #[builder(default)] invites: Vec<UserId>,
 ) -> CreateEmbed {
     let mut embed = CreateEmbed::new()
         .title(format!("[{}] {}", abbreviation, title))
@@ -117,10 +345,34 @@ pub fn game_embed(
         .timestamp(created_at)
         .field("Role", role_id.mention().to_string(), true);
 
+    if let Some(active_since) = active_since {
+        embed = embed.field(
+            "Active",
+            format!("since <t:{}:R>", active_since.timestamp()),
+            true,
+        );
+    }
+
+    if open {
+        embed = embed.field("Open", "Join with `/game join`", true);
+    }
+
     if let Some(channel_id) = channel_id {
         embed = embed.field("Main Channel", channel_id.mention().to_string(), true);
     }
 
+    if !channels.is_empty() {
+        embed = embed.field(
+            "Channels",
+            channels
+                .into_iter()
+                .map(|(channel_id, purpose)| format!("{} ({purpose})", channel_id.mention()))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            true,
+        );
+    }
+
     if let Some(system_abbreviation) = system {
         embed = embed.field("System", system_abbreviation, true);
     }
@@ -143,6 +395,18 @@ pub fn game_embed(
         false,
     );
 
+    if !invites.is_empty() {
+        embed = embed.field(
+            "Pending Invites",
+            invites
+                .into_iter()
+                .map(|i| i.mention().to_string())
+                .collect::<Vec<String>>()
+                .join(" "),
+            false,
+        );
+    }
+
     if let Some(description) = description {
         embed = embed.field("Description", description, false);
     }
@@ -153,3 +417,151 @@ pub fn game_embed(
 
     embed
 }
+
+const RETRY_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Embed descriptions can hold at most this many characters.
+const MAX_DESCRIPTION_LENGTH: usize = 4096;
+
+/// The longest a player's line in a nickname report can be, so one long error can't crowd out
+/// everyone else.
+const MAX_REPORT_LINE_LENGTH: usize = 200;
+
+fn nickname_report_embed(outcomes: &[(UserId, Outcome)]) -> CreateEmbed {
+    let description = if outcomes.is_empty() {
+        "No players to update.".to_string()
+    } else {
+        // Failures come first, so they're still shown when the report has to be cut short.
+        let mut lines: Vec<String> = outcomes
+            .iter()
+            .filter(|(_, o)| o.is_failed())
+            .chain(outcomes.iter().filter(|(_, o)| !o.is_failed()))
+            .map(|(user_id, outcome)| {
+                let line = outcome.describe(*user_id);
+                if line.chars().count() > MAX_REPORT_LINE_LENGTH {
+                    format!(
+                        "{}…",
+                        line.chars()
+                            .take(MAX_REPORT_LINE_LENGTH - 1)
+                            .collect::<String>()
+                    )
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        // Leave room for the line saying how many were left out.
+        let mut length = "…and 0000 more.".len();
+        let shown = lines
+            .iter()
+            .take_while(|line| {
+                length += line.chars().count() + 1;
+                length <= MAX_DESCRIPTION_LENGTH
+            })
+            .count();
+        let hidden = lines.len() - shown;
+        lines.truncate(shown);
+        if hidden > 0 {
+            lines.push(format!("…and {hidden} more."));
+        }
+        lines.join("\n")
+    };
+
+    CreateEmbed::new()
+        .title("Nicknames")
+        .description(description)
+}
+
+fn retry_button(ctx: Context<'_>, outcomes: &[(UserId, Outcome)]) -> Vec<CreateActionRow> {
+    let failures = outcomes.iter().filter(|(_, o)| o.is_failed()).count();
+    if failures == 0 {
+        vec![]
+    } else {
+        vec![CreateActionRow::Buttons(vec![CreateButton::new(format!(
+            "{}retry",
+            ctx.id()
+        ))
+        .label(format!("Retry {failures} failed"))
+        .style(ButtonStyle::Primary)])]
+    }
+}
+
+/// Reply with what happened to each player's nickname.
+///
+/// Follow up with [`retry_failed_nicknames`] once anything else has been said.
+pub async fn nickname_report<'a>(
+    ctx: Context<'a>,
+    content: String,
+    outcomes: &[(UserId, Outcome)],
+) -> Result<ReplyHandle<'a>> {
+    Ok(ctx
+        .send(
+            CreateReply::default()
+                .content(content)
+                .embed(nickname_report_embed(outcomes))
+                .components(retry_button(ctx, outcomes)),
+        )
+        .await?)
+}
+
+/// Let the author retry any failed nickname changes from a [`nickname_report`] until they all succeed or it times out.
+pub async fn retry_failed_nicknames(
+    ctx: Context<'_>,
+    handle: ReplyHandle<'_>,
+    mut outcomes: Vec<(UserId, Outcome)>,
+) -> Result<()> {
+    let ctx_id = ctx.id();
+
+    while outcomes.iter().any(|(_, o)| o.is_failed()) {
+        let press = match ComponentInteractionCollector::new(ctx)
+            .author_id(ctx.author().id)
+            .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+            .timeout(RETRY_TIMEOUT)
+            .await
+        {
+            Some(press) => press,
+            None => {
+                handle
+                    .edit(
+                        poise::Context::Application(ctx),
+                        CreateReply::default()
+                            .embed(nickname_report_embed(&outcomes))
+                            .components(vec![]),
+                    )
+                    .await?;
+                break;
+            }
+        };
+
+        press
+            .create_response(ctx, CreateInteractionResponse::Acknowledge)
+            .await?;
+
+        let failed = outcomes
+            .iter()
+            .filter(|(_, o)| o.is_failed())
+            .map(|(user_id, _)| *user_id)
+            .collect();
+        let retried =
+            crate::nickname::settle_all(&ctx, &ctx.data().pool, ctx.guild_id().unwrap(), failed)
+                .await;
+
+        for (user_id, outcome) in retried {
+            if let Some(entry) = outcomes.iter_mut().find(|(u, _)| *u == user_id) {
+                entry.1 = outcome;
+            }
+        }
+
+        handle
+            .edit(
+                poise::Context::Application(ctx),
+                CreateReply::default()
+                    .embed(nickname_report_embed(&outcomes))
+                    .components(retry_button(ctx, &outcomes)),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
@@ -1,17 +1,16 @@
 use poise::CreateReply;
-use serenity::all::{EditMember, Member, Mentionable, UserId};
+use serenity::all::{Mentionable, UserId};
 use sqlx::query;
 
 use crate::{
-    commands::{contextual_args, game::can_manage},
-    Context, Result,
+    commands::{
+        contextual_args,
+        game::{can_manage, nickname_report, retry_failed_nicknames},
+    },
+    nickname::{self, Outcome},
+    Context, Error, Result,
 };
 
-fn apply_character_name(member: &Member, character_name: String) -> String {
-    let base_name = member.display_name().split("(").next().unwrap().trim_end();
-    format!("{base_name} ({character_name})")
-}
-
 /// Activate this game, assigning all players' nicknames. Usable by game owners and server moderators.
 #[poise::command(slash_command)]
 pub async fn activate(
@@ -42,59 +41,67 @@ pub async fn activate(
     .fetch_all(&ctx.data().pool)
     .await?;
 
-    let game_title = query!(
+    let maybe_activated = query!(
         r#"
-        select title
-        from games
-        where id = $1
+        update games
+        set active_since = now()
+        where id = $1 and active_since is null
+        returning title
         "#,
         game
     )
-    .fetch_one(&ctx.data().pool)
-    .await?
-    .title;
+    .fetch_optional(&ctx.data().pool)
+    .await?;
+
+    let game_title = match maybe_activated {
+        Some(activated) => activated.title,
+        None => {
+            return Err(Error::Message(
+                "This game is already active! Deactivate it first.".to_string(),
+            ));
+        }
+    };
 
+    let template = nickname::template(&ctx.data().pool, game).await?;
+
+    let guild_id = ctx.guild_id().unwrap();
     let owner_id = ctx.guild().unwrap().owner_id;
     let mut owner_character_name: Option<String> = None;
 
-    let mut changes = vec![];
+    let mut outcomes = vec![];
+    let mut claimed = vec![];
 
     for player in players {
         let player_id = UserId::from(player.user_id as u64);
         if player_id == owner_id {
+            outcomes.push((player_id, Outcome::Skipped("server owner".to_string())));
             owner_character_name = player.character_name;
             continue;
         }
 
-        if let Some(character_name) = player.character_name {
-            let member = ctx.guild_id().unwrap().member(ctx, player_id).await?;
-            let nick_name = apply_character_name(&member, character_name);
-
-            changes.push(format!("{} --> {}", member.display_name(), nick_name));
-
-            ctx.guild_id()
-                .unwrap()
-                .edit_member(
-                    ctx,
-                    player_id,
-                    EditMember::new()
-                        .nickname(nick_name)
-                        .audit_log_reason("Game activated by command"),
-                )
-                .await?;
+        if player.character_name.is_some() {
+            nickname::claim(&ctx.data().pool, game, guild_id, player_id).await?;
+            claimed.push(player_id);
+        } else {
+            outcomes.push((player_id, Outcome::Skipped("no character".to_string())));
         }
     }
 
-    ctx.say(format!(
-        "`{game_title}` activated!\n```\n{}\n```",
-        changes.join("\n")
-    ))
-    .await?;
+    ctx.defer().await?;
+
+    outcomes.extend(nickname::settle_all(&ctx, &ctx.data().pool, guild_id, claimed).await);
+
+    let report = nickname_report(ctx, format!("`{game_title}` activated!"), &outcomes).await?;
 
     if let Some(character_name) = owner_character_name {
-        let owner = ctx.guild_id().unwrap().member(ctx, owner_id).await?;
+        let owner = guild_id.member(ctx, owner_id).await?;
+        let original = nickname::snapshot(&ctx.data().pool, guild_id, &owner).await?;
         let why = "[Why?](<https://github.com/Drowrin/eurydice/wiki/Why-is-the-bot-telling-me-to-use-a-nick-command>)";
-        let nick_name = apply_character_name(&owner, character_name);
+        let nick_name = nickname::render(
+            &template,
+            &character_name,
+            &nickname::base_name(original.as_deref(), &owner),
+        );
 
         if owner_id == ctx.author().id {
             ctx.send(
@@ -112,5 +119,5 @@ pub async fn activate(
         }
     }
 
-    Ok(())
+    retry_failed_nicknames(ctx, report, outcomes).await
 }
@@ -0,0 +1,54 @@
+use poise::Modal;
+use sqlx::query;
+
+use crate::{commands::game::can_manage, Context, Error, Result};
+
+mod edit;
+mod list;
+mod post;
+mod repost;
+
+#[poise::command(
+    slash_command,
+    subcommand_required,
+    subcommands("post::post", "list::list", "edit::edit", "repost::repost"),
+    guild_only
+)]
+pub async fn announce(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Debug, Default, Modal)]
+#[name = "Announcement"]
+pub struct AnnouncementModal {
+    #[name = "Title"]
+    #[min_length = 1]
+    #[max_length = 256]
+    title: String,
+    #[name = "Announcement"]
+    #[min_length = 1]
+    #[max_length = 4000]
+    #[paragraph]
+    content: String,
+}
+
+/// Find the game an announcement was made for, making sure the author can manage it.
+pub async fn announcement_game(ctx: Context<'_>, announcement: i32) -> Result<i32> {
+    let game = query!(
+        r#"
+        select game_id
+        from announcements
+        where id = $1 and guild_id = $2
+        "#,
+        announcement,
+        ctx.guild_id().unwrap().get() as i64,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?
+    .ok_or(Error::NotFound)?
+    .game_id;
+
+    can_manage(ctx, game).await?;
+
+    Ok(game)
+}
@@ -0,0 +1,103 @@
+use poise::Modal;
+use sqlx::{query, types::chrono::Utc};
+
+use crate::{
+    announcements::{self, parse_time},
+    commands::game::announce::{announcement_game, AnnouncementModal},
+    Context, Error, Result,
+};
+
+/// Edit an announcement, even after it was posted. Usable by game owners and server moderators.
+#[poise::command(slash_command, ephemeral)]
+pub async fn edit(
+    ctx: Context<'_>,
+    #[description = "The announcement to edit"]
+    #[autocomplete = "crate::autocomplete::announcement_editable"]
+    announcement: i32,
+    #[description = "Reschedule it, as YYYY-MM-DD HH:MM in UTC. Only for unposted announcements"]
+    at: Option<String>,
+) -> Result<()> {
+    announcement_game(ctx, announcement).await?;
+
+    let old = query!(
+        r#"
+        select title, content, posted_at is not null as "posted!"
+        from announcements
+        where id = $1
+        "#,
+        announcement,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?;
+
+    let post_at = match at.as_deref().map(parse_time) {
+        None => None,
+        Some(_) if old.posted => {
+            return Err(Error::Message(
+                "That announcement has already been posted. Use `/game announce repost` to post it again."
+                    .to_string(),
+            ));
+        }
+        Some(Some(post_at)) if post_at > Utc::now() => Some(post_at),
+        Some(Some(_)) => {
+            return Err(Error::Message(
+                "That time has already passed. Times are in UTC.".to_string(),
+            ));
+        }
+        Some(None) => {
+            return Err(Error::Message(
+                "I couldn't read that time. Write it like `2024-10-31 19:30`, in UTC.".to_string(),
+            ));
+        }
+    };
+
+    let defaults = AnnouncementModal {
+        title: old.title,
+        content: old.content,
+    };
+    let announcement_data = match AnnouncementModal::execute_with_defaults(ctx, defaults).await? {
+        Some(announcement_data) => announcement_data,
+        None => return Ok(()),
+    };
+
+    query!(
+        r#"
+        update announcements
+        set
+            title = $2,
+            content = $3,
+            post_at = coalesce($4, post_at),
+            failed_at = case when $4::timestamptz is null then failed_at end
+        where id = $1
+        "#,
+        announcement,
+        announcement_data.title,
+        announcement_data.content,
+        post_at,
+    )
+    .execute(&ctx.data().pool)
+    .await?;
+
+    if let Err(e) = announcements::update(ctx.http(), &ctx.data().pool, announcement).await {
+        println!("Error updating announcement {}: {}", announcement, e);
+        return Err(Error::Message(
+            "Saved, but I couldn't update the posted message. It may have been deleted; try `/game announce repost`."
+                .to_string(),
+        ));
+    }
+
+    match post_at {
+        Some(post_at) => {
+            ctx.say(format!(
+                "Announcement updated and rescheduled for <t:{}:F>.",
+                post_at.timestamp()
+            ))
+            .await?;
+        }
+        None => {
+            ctx.say("Announcement updated!").await?;
+        }
+    }
+
+    Ok(())
+}
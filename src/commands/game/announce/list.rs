@@ -0,0 +1,79 @@
+use serenity::all::{ChannelId, Mentionable};
+use sqlx::query;
+
+use crate::{
+    commands::{contextual_args, game::can_manage, paginate},
+    Context, Result,
+};
+
+/// List this game's announcements, past and scheduled. Usable by game owners and server moderators.
+#[poise::command(slash_command, ephemeral)]
+pub async fn list(
+    ctx: Context<'_>,
+    #[description = "The game to list announcements for"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    let title = query!(
+        r#"
+        select title
+        from games
+        where id = $1
+        "#,
+        game,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?
+    .title;
+
+    let announcements = query!(
+        r#"
+        select
+            title, channel_id, post_at, posted_at, failed_at
+        from announcements
+        where game_id = $1
+        order by post_at desc
+        "#,
+        game,
+    )
+    .fetch_all(&ctx.data().pool)
+    .await?;
+
+    let lines = announcements
+        .into_iter()
+        .map(|announcement| {
+            let when = match (announcement.posted_at, announcement.failed_at) {
+                (Some(posted_at), _) => format!("posted <t:{}:R>", posted_at.timestamp()),
+                (None, Some(failed_at)) => format!(
+                    "couldn't be posted <t:{}:R>, use `/game announce repost`",
+                    failed_at.timestamp()
+                ),
+                (None, None) => format!("scheduled for <t:{}:F>", announcement.post_at.timestamp()),
+            };
+            format!(
+                "**{}** · {when} in {}",
+                announcement.title,
+                ChannelId::from(announcement.channel_id as u64).mention()
+            )
+        })
+        .collect();
+
+    paginate()
+        .ctx(&ctx)
+        .title(&format!("Announcements for {title}"))
+        .lines(lines)
+        .empty_message("No announcements yet! Make one with `/game announce post`.")
+        .call()
+        .await?;
+
+    Ok(())
+}
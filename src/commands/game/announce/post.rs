@@ -0,0 +1,137 @@
+use poise::Modal;
+use serenity::all::{Channel, ChannelId, Mentionable};
+use sqlx::{query, types::chrono::Utc};
+
+use crate::{
+    announcements::{self, parse_time},
+    commands::{
+        contextual_args,
+        game::{announce::AnnouncementModal, can_manage},
+    },
+    Context, Error, Result,
+};
+
+/// Announce something to the players, now or later. Usable by game owners and server moderators.
+#[poise::command(slash_command, ephemeral)]
+pub async fn post(
+    ctx: Context<'_>,
+    #[description = "The game to make an announcement for"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+    #[description = "Where to post it. Defaults to the game's main channel"]
+    #[channel_types("Text", "News")]
+    channel: Option<Channel>,
+    #[description = "When to post it, as YYYY-MM-DD HH:MM in UTC. Defaults to now"] at: Option<
+        String,
+    >,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    let post_at = match at.as_deref().map(parse_time) {
+        None => None,
+        Some(Some(post_at)) if post_at > Utc::now() => Some(post_at),
+        Some(Some(_)) => {
+            return Err(Error::Message(
+                "That time has already passed. Times are in UTC.".to_string(),
+            ));
+        }
+        Some(None) => {
+            return Err(Error::Message(
+                "I couldn't read that time. Write it like `2024-10-31 19:30`, in UTC.".to_string(),
+            ));
+        }
+    };
+
+    let channel_id = match channel {
+        Some(channel) => channel.id(),
+        None => query!(
+            r#"
+            select main_channel_id
+            from games
+            where id = $1
+            "#,
+            game,
+        )
+        .fetch_one(&ctx.data().pool)
+        .await?
+        .main_channel_id
+        .map(|c| ChannelId::from(c as u64))
+        .ok_or_else(|| {
+            Error::Message(
+                "This game doesn't have a main channel. Choose a `channel`, or set one with `/game channel set`."
+                    .to_string(),
+            )
+        })?,
+    };
+
+    let announcement_data = match AnnouncementModal::execute(ctx).await? {
+        Some(announcement_data) => announcement_data,
+        None => return Ok(()),
+    };
+
+    // Announcements posted now are marked as posted straight away, so the scheduler leaves them alone.
+    let announcement = query!(
+        r#"
+        insert
+        into announcements
+            (game_id, guild_id, author_id, channel_id, title, content, post_at, posted_at)
+        values
+            ($1, $2, $3, $4, $5, $6, coalesce($7, now()), case when $7 is null then now() end)
+        returning id
+        "#,
+        game,
+        ctx.guild_id().unwrap().get() as i64,
+        ctx.author().id.get() as i64,
+        channel_id.get() as i64,
+        announcement_data.title,
+        announcement_data.content,
+        post_at,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?
+    .id;
+
+    match post_at {
+        Some(post_at) => {
+            ctx.say(format!(
+                "Announcement scheduled for <t:{}:F> in {}.",
+                post_at.timestamp(),
+                channel_id.mention()
+            ))
+            .await?;
+        }
+        None => {
+            if let Err(e) = announcements::post(ctx.http(), &ctx.data().pool, announcement).await {
+                println!("Error posting announcement {}: {}", announcement, e);
+                query!(
+                    r#"
+                    update announcements
+                    set
+                        posted_at = null,
+                        failed_at = now()
+                    where id = $1
+                    "#,
+                    announcement,
+                )
+                .execute(&ctx.data().pool)
+                .await?;
+                return Err(Error::Message(format!(
+                    "I couldn't post in {}. Check my permissions there, then try `/game announce repost`.",
+                    channel_id.mention()
+                )));
+            }
+
+            ctx.say(format!("Announcement posted in {}!", channel_id.mention()))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
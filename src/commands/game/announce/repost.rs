@@ -0,0 +1,76 @@
+use serenity::all::{Channel, Mentionable};
+use sqlx::query;
+
+use crate::{announcements, commands::game::announce::announcement_game, Context, Error, Result};
+
+/// Post an announcement again now. Usable by game owners and server moderators.
+#[poise::command(slash_command, ephemeral)]
+pub async fn repost(
+    ctx: Context<'_>,
+    #[description = "The announcement to repost"]
+    #[autocomplete = "crate::autocomplete::announcement_editable"]
+    announcement: i32,
+    #[description = "Where to post it. Defaults to where it was posted before"]
+    #[channel_types("Text", "News")]
+    channel: Option<Channel>,
+) -> Result<()> {
+    announcement_game(ctx, announcement).await?;
+
+    // Marking it as posted first keeps the scheduler from posting it too. If posting fails,
+    // it's put back the way it was.
+    let previous = query!(
+        r#"
+        with old as (
+            select posted_at, channel_id
+            from announcements
+            where id = $1
+        )
+        update announcements
+        set
+            channel_id = coalesce($2, channel_id),
+            posted_at = now()
+        where id = $1
+        returning
+            (select posted_at from old),
+            (select channel_id from old) as "channel_id!"
+        "#,
+        announcement,
+        channel.map(|c| c.id().get() as i64),
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?;
+
+    match announcements::post(ctx.http(), &ctx.data().pool, announcement).await {
+        Ok((channel_id, _)) => {
+            ctx.say(format!(
+                "Announcement reposted in {}!",
+                channel_id.mention()
+            ))
+            .await?;
+        }
+        Err(e) => {
+            println!("Error posting announcement {}: {}", announcement, e);
+            query!(
+                r#"
+                update announcements
+                set
+                    channel_id = $3,
+                    posted_at = $2,
+                    failed_at = case when $2::timestamptz is null then now() end
+                where id = $1
+                "#,
+                announcement,
+                previous.posted_at,
+                previous.channel_id,
+            )
+            .execute(&ctx.data().pool)
+            .await?;
+            return Err(Error::Message(
+                "I couldn't post the announcement. Check my permissions in that channel."
+                    .to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -1,3 +1,6 @@
+use serenity::all::ChannelId;
+use sqlx::query;
+
 use crate::{Context, Result};
 
 mod set;
@@ -12,3 +15,37 @@ mod unset;
 pub async fn channel(_: Context<'_>) -> Result<()> {
     Ok(())
 }
+
+/// Check whether a channel is already linked to a game other than this one.
+pub async fn is_taken(ctx: Context<'_>, game: i32, channel: ChannelId) -> Result<bool> {
+    Ok(query!(
+        r#"
+        select exists (
+            select 1
+            from games
+            where
+                guild_id = $1
+                and
+                id != $2
+                and
+                main_channel_id = $3
+        )
+        or exists (
+            select 1
+            from game_channels
+            where
+                guild_id = $1
+                and
+                game_id != $2
+                and
+                channel_id = $3
+        ) as "taken!"
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        game,
+        channel.get() as i64,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?
+    .taken)
+}
@@ -1,7 +1,10 @@
 use serenity::all::{Channel, Mentionable};
 use sqlx::query;
 
-use crate::{commands::game::can_manage, Context, Result};
+use crate::{
+    commands::game::{can_manage, channel::is_taken},
+    Context, Error, Result,
+};
 
 /// Associate this game with a channel. This makes some other commands easier to use in this channel.
 #[poise::command(slash_command, ephemeral)]
@@ -11,45 +14,89 @@ pub async fn set(
     #[autocomplete = "crate::autocomplete::game_editable"]
     game: i32,
     #[description = "Channel that will be associated with the game"]
-    #[channel_types("Text")]
+    #[channel_types("Text", "Voice", "Stage", "News", "Forum")]
     channel: Channel,
+    #[description = "What the channel is for, such as OOC. Leave empty to set the main channel"]
+    #[min_length = 1]
+    #[max_length = 32]
+    purpose: Option<String>,
 ) -> Result<()> {
     can_manage(ctx, game).await?;
 
-    let record = query!(
-        r#"
-        update games
-        set main_channel_id = $3
-        where guild_id = $1 and id = $2
-        returning title
-        "#,
-        ctx.guild_id().unwrap().get() as i64,
-        game,
-        channel.id().get() as i64,
-    )
-    .fetch_one(&ctx.data().pool)
-    .await;
-
-    match record {
-        Ok(record) => {
+    if is_taken(ctx, game, channel.id()).await? {
+        ctx.say(format!(
+            "Another game has already taken {}.",
+            channel.mention(),
+        ))
+        .await?;
+
+        return Ok(());
+    }
+
+    match purpose {
+        Some(purpose) => {
+            let record = query!(
+                r#"
+                insert
+                into game_channels
+                    (channel_id, game_id, guild_id, purpose)
+                select $3, id, guild_id, $4
+                from games
+                where guild_id = $1 and id = $2
+                on conflict (channel_id) do update
+                set purpose = excluded.purpose
+                returning (select title from games where id = $2)
+                "#,
+                ctx.guild_id().unwrap().get() as i64,
+                game,
+                channel.id().get() as i64,
+                purpose,
+            )
+            .fetch_optional(&ctx.data().pool)
+            .await?
+            .ok_or(Error::NotFound)?;
+
             ctx.say(format!(
-                "Set channel of `{}` to {}.",
-                record.title,
+                "Linked {} to `{}` for {purpose}.",
                 channel.mention(),
+                record.title.unwrap(),
             ))
             .await?;
-
-            Ok(())
         }
-        Err(sqlx::Error::Database(_)) => {
+        None => {
+            let record = query!(
+                r#"
+                update games
+                set main_channel_id = $3
+                where guild_id = $1 and id = $2
+                returning title
+                "#,
+                ctx.guild_id().unwrap().get() as i64,
+                game,
+                channel.id().get() as i64,
+            )
+            .fetch_one(&ctx.data().pool)
+            .await?;
+
+            query!(
+                r#"
+                delete
+                from game_channels
+                where channel_id = $1
+                "#,
+                channel.id().get() as i64,
+            )
+            .execute(&ctx.data().pool)
+            .await?;
+
             ctx.say(format!(
-                "Another game has already taken {}.",
+                "Set channel of `{}` to {}.",
+                record.title,
                 channel.mention(),
             ))
             .await?;
-
-            Ok(())
         }
-        Err(e) => Err(e.into()),
     }
+
+    Ok(())
 }
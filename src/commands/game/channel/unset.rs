@@ -1,17 +1,19 @@
+use serenity::all::{Channel, Mentionable};
 use sqlx::query;
 
 use crate::{
     commands::{contextual_args, game::can_manage},
-    Context, Result,
+    Context, Error, Result,
 };
 
-/// Remove this game's association with any channel.
+/// Remove this game's association with a channel, or with its main channel if none is given.
 #[poise::command(slash_command, ephemeral)]
 pub async fn unset(
     ctx: Context<'_>,
     #[description = "The game to unset the channel of"]
     #[autocomplete = "crate::autocomplete::game_editable"]
     game: Option<i32>,
+    #[description = "The linked channel to remove"] channel: Option<Channel>,
 ) -> Result<()> {
     let game = contextual_args()
         .game_id_arg(game)
@@ -26,17 +28,59 @@ pub async fn unset(
         r#"
         update games
         set main_channel_id = null
-        where guild_id = $1 and id = $2
+        where
+            guild_id = $1
+            and
+            id = $2
+            and
+            ($3::bigint is null or main_channel_id = $3)
         returning title
         "#,
         ctx.guild_id().unwrap().get() as i64,
         game,
+        channel.as_ref().map(|c| c.id().get() as i64),
     )
-    .fetch_one(&ctx.data().pool)
+    .fetch_optional(&ctx.data().pool)
     .await?;
 
-    ctx.say(format!("Unset channel of `{}`.", record.title))
-        .await?;
+    if let Some(record) = record {
+        ctx.say(format!("Unset channel of `{}`.", record.title))
+            .await?;
+        return Ok(());
+    }
+
+    let channel = match channel {
+        Some(channel) => channel,
+        None => return Err(Error::NotFound),
+    };
+
+    let record = query!(
+        r#"
+        delete
+        from game_channels
+        where game_id = $1 and channel_id = $2
+        returning (select title from games where id = $1)
+        "#,
+        game,
+        channel.id().get() as i64,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?;
+
+    match record {
+        Some(record) => {
+            ctx.say(format!(
+                "Unlinked {} from `{}`.",
+                channel.mention(),
+                record.title.unwrap(),
+            ))
+            .await?;
+        }
+        None => {
+            ctx.say(format!("{} isn't linked to that game.", channel.mention()))
+                .await?;
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,119 @@
+use poise::CreateReply;
+use serenity::all::Channel;
+use sqlx::query;
+
+use crate::{
+    commands::{
+        contextual_args,
+        game::{can_manage, check_available, game_embed, insert_game},
+    },
+    Context, Error, Result,
+};
+
+/// Start a new game from a copy of this one. Usable by game owners and server moderators.
+#[poise::command(slash_command, ephemeral)]
+pub async fn clone(
+    ctx: Context<'_>,
+    #[description = "Title of the new game"]
+    #[min_length = 3]
+    #[max_length = 100]
+    title: String,
+    #[description = "Abbreviation of the new game's title, used for the role name"]
+    #[min_length = 3]
+    #[max_length = 32]
+    abbreviation: String,
+    #[description = "The game to copy"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+    #[description = "Also copy the game's characters, unassigned"] characters: Option<bool>,
+    #[description = "Channel that will be associated with the new game"]
+    #[channel_types("Text")]
+    channel: Option<Channel>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    check_available(ctx, &title, &abbreviation, channel.as_ref().map(|c| c.id())).await?;
+
+    let source = query!(
+        r#"
+        select
+            title, description, image, system_id, nickname_template
+        from games
+        where id = $1 and guild_id = $2
+        "#,
+        game,
+        ctx.guild_id().unwrap().get() as i64,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?
+    .ok_or(Error::NotFound)?;
+
+    ctx.defer_ephemeral().await?;
+
+    let new_game = insert_game()
+        .ctx(&ctx)
+        .title(&title)
+        .abbreviation(&abbreviation)
+        .maybe_description(source.description.as_deref())
+        .maybe_image(source.image.as_deref())
+        .maybe_system(source.system_id)
+        .maybe_channel(channel.as_ref().map(|c| c.id()))
+        .maybe_nickname_template(source.nickname_template.as_deref())
+        .call()
+        .await?;
+
+    let mut content = format!("Cloned `{}`!", source.title);
+
+    if characters.unwrap_or_default() {
+        let copied = query!(
+            r#"
+            insert
+            into characters
+                (
+                    guild_id, author_id, game_id,
+                    name, pronouns, description, image, fields
+                )
+            select
+                guild_id, author_id, $2,
+                name, pronouns, description, image, fields
+            from characters
+            where game_id = $1
+            "#,
+            game,
+            new_game.id,
+        )
+        .execute(&ctx.data().pool)
+        .await?
+        .rows_affected();
+
+        content = format!("{content} {copied} characters were copied.");
+    }
+
+    ctx.send(
+        CreateReply::default().content(content).embed(
+            game_embed()
+                .title(title)
+                .abbreviation(abbreviation)
+                .description(source.description)
+                .image(source.image)
+                .system(new_game.system)
+                .created_at(new_game.created_at)
+                .active_since(None)
+                .role_id(new_game.role_id)
+                .channel_id(channel.map(|c| c.id()))
+                .owner_id(ctx.author().id)
+                .players(vec![])
+                .call(),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1,10 +1,10 @@
 use poise::{CreateReply, Modal};
-use serenity::all::{Channel, EditRole, Mentionable};
+use serenity::all::Channel;
 use sqlx::query;
 
 use crate::{
-    commands::game::{game_embed, GameModal},
-    Context, Result,
+    commands::game::{check_available, game_embed, insert_game, GameModal},
+    Context, Error, Result,
 };
 
 /// Create a new game in this server. Usable by anyone.
@@ -25,107 +25,101 @@ pub async fn create(
     #[description = "Channel that will be associated with the game"]
     #[channel_types("Text")]
     channel: Option<Channel>,
+    #[description = "A template to start the game from"]
+    #[autocomplete = "crate::autocomplete::template"]
+    template: Option<i32>,
 ) -> Result<()> {
-    let maybe_already_exists = query!(
-        r#"
-        select
-            title, abbreviation, main_channel_id
-        from games
-        where
-            guild_id = $2
-            and
-            (
-                title = $1
-                or
-                abbreviation = $1
-                or
-                main_channel_id = $3
-            )
-        "#,
-        title,
-        ctx.guild_id().unwrap().get() as i64,
-        channel.clone().map(|c| c.id().get() as i64)
-    )
-    .fetch_optional(&ctx.data().pool)
-    .await?;
+    check_available(ctx, &title, &abbreviation, channel.as_ref().map(|c| c.id())).await?;
 
-    if let Some(already_exists) = maybe_already_exists {
-        if title == already_exists.title {
-            ctx.reply(format!("`{title}` already exists!")).await?;
-        } else if abbreviation == already_exists.abbreviation {
-            ctx.reply(format!("`{abbreviation}` is already taken!"))
-                .await?;
-        } else if channel.clone().map(|c| c.id().get() as i64) == already_exists.main_channel_id {
-            let var_name = format!("{} is already taken!", channel.unwrap().mention());
-            ctx.reply(var_name).await?;
-        }
-        return Ok(());
-    }
+    let template = match template {
+        Some(template) => Some(
+            query!(
+                r#"
+                select
+                    id, system_id, description, image, nickname_template
+                from game_templates
+                where id = $1 and guild_id = $2
+                "#,
+                template,
+                ctx.guild_id().unwrap().get() as i64,
+            )
+            .fetch_optional(&ctx.data().pool)
+            .await?
+            .ok_or(Error::NotFound)?,
+        ),
+        None => None,
+    };
 
     let defaults = GameModal {
         abbreviation,
         title,
-        ..Default::default()
+        description: template.as_ref().and_then(|t| t.description.clone()),
+        image: template.as_ref().and_then(|t| t.image.clone()),
     };
     let maybe_game_data = GameModal::execute_with_defaults(ctx, defaults).await?;
 
     if let Some(game_data) = maybe_game_data {
-        let role = ctx
-            .guild_id()
-            .unwrap()
-            .create_role(
-                ctx,
-                EditRole::new()
-                    .name(game_data.abbreviation.clone())
-                    .audit_log_reason("Game role created")
-                    .mentionable(true),
+        // A system chosen here wins over the template's.
+        let system = system.or(template.as_ref().and_then(|t| t.system_id));
+
+        let new_game = insert_game()
+            .ctx(&ctx)
+            .title(&game_data.title)
+            .abbreviation(&game_data.abbreviation)
+            .maybe_description(game_data.description.as_deref())
+            .maybe_image(game_data.image.as_deref())
+            .maybe_system(system)
+            .maybe_channel(channel.as_ref().map(|c| c.id()))
+            .maybe_nickname_template(
+                template
+                    .as_ref()
+                    .and_then(|t| t.nickname_template.as_deref()),
             )
+            .call()
             .await?;
 
-        ctx.author_member()
-            .await
-            .unwrap()
-            .add_role(ctx, role.id)
-            .await?;
+        let mut content = "Game created!".to_string();
 
-        let returned_game_data = query!(
-            r#"
-            insert
-            into games
-                (
-                    title, abbreviation, description, image,
-                    guild_id, owner_id, role_id, system_id,
-                    main_channel_id
-                )
-            values
-                ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            returning
-                created_at,
-                (select abbreviation from systems where id = $8) as "system"
-            "#,
-            game_data.title.clone(),
-            game_data.abbreviation.clone(),
-            game_data.description.clone(),
-            game_data.image.clone(),
-            ctx.guild_id().unwrap().get() as i64,
-            ctx.author().id.get() as i64,
-            role.id.get() as i64,
-            system,
-            channel.clone().map(|c| c.id().get() as i64),
-        )
-        .fetch_one(&ctx.data().pool)
-        .await?;
+        if let Some(template) = &template {
+            let copied = query!(
+                r#"
+                insert
+                into characters
+                    (
+                        guild_id, author_id, game_id,
+                        name, pronouns, description, image, fields
+                    )
+                select
+                    $2, $3, $4,
+                    name, pronouns, description, image, fields
+                from template_characters
+                where template_id = $1
+                "#,
+                template.id,
+                ctx.guild_id().unwrap().get() as i64,
+                ctx.author().id.get() as i64,
+                new_game.id,
+            )
+            .execute(&ctx.data().pool)
+            .await?
+            .rows_affected();
+
+            if copied > 0 {
+                content = format!("{content} {copied} characters were copied from the template.");
+            }
+        }
 
         ctx.send(
-            CreateReply::default().content("Game created!").embed(
+            CreateReply::default().content(content).embed(
                 game_embed()
                     .title(game_data.title)
                     .abbreviation(game_data.abbreviation)
                     .description(game_data.description)
                     .image(game_data.image)
-                    .system(returned_game_data.system)
-                    .created_at(returned_game_data.created_at)
-                    .role_id(role.id)
+                    .system(new_game.system)
+                    .created_at(new_game.created_at)
+                    .active_since(None)
+                    .role_id(new_game.role_id)
                     .channel_id(channel.map(|c| c.id()))
                     .owner_id(ctx.author().id)
                     .players(vec![])
@@ -1,23 +1,17 @@
 use poise::CreateReply;
-use serenity::all::{EditMember, Member, Mentionable, UserId};
+use serenity::all::{Mentionable, UserId};
 use sqlx::query;
 
 use crate::{
-    commands::{contextual_args, game::can_manage},
+    commands::{
+        contextual_args,
+        game::{can_manage, nickname_report, retry_failed_nicknames},
+    },
+    nickname::{self, Outcome},
     Context, Result,
 };
 
-fn strip_character_name(member: &Member) -> String {
-    member
-        .display_name()
-        .split("(")
-        .next()
-        .unwrap()
-        .trim_end()
-        .to_string()
-}
-
-/// Activate this game, reverting all players' nicknames. Usable by game owners and server moderators.
+/// Deactivate this game, reverting all players' nicknames. Usable by game owners and server moderators.
 #[poise::command(slash_command)]
 pub async fn deactivate(
     ctx: Context<'_>,
@@ -46,11 +40,13 @@ pub async fn deactivate(
     .fetch_all(&ctx.data().pool)
     .await?;
 
+    // Deactivating an inactive game is allowed, so leftover nicknames can always be cleaned up.
     let game_title = query!(
         r#"
-        select title
-        from games
+        update games
+        set active_since = null
         where id = $1
+        returning title
         "#,
         game
     )
@@ -58,63 +54,75 @@ pub async fn deactivate(
     .await?
     .title;
 
+    let guild_id = ctx.guild_id().unwrap();
     let owner_id = ctx.guild().unwrap().owner_id;
     let mut owner_found: bool = false;
 
-    let mut changes = vec![];
+    let mut outcomes = vec![];
+    let mut released = vec![];
 
     for player in players {
         let player_id = UserId::from(player.user_id as u64);
         if player_id == owner_id {
+            outcomes.push((player_id, Outcome::Skipped("server owner".to_string())));
             owner_found = true;
             continue;
         }
 
-        let member = ctx.guild_id().unwrap().member(ctx, player_id).await?;
-        let nick_name = strip_character_name(&member);
-
-        changes.push(format!("{} --> {}", member.display_name(), nick_name));
-
-        ctx.guild_id()
-            .unwrap()
-            .edit_member(
-                ctx,
-                player_id,
-                EditMember::new()
-                    .nickname(nick_name)
-                    .audit_log_reason("Game deactivated by command"),
-            )
-            .await?;
+        nickname::unclaim(&ctx.data().pool, game, player_id).await?;
+        released.push(player_id);
     }
 
-    ctx.say(format!(
-        "`{game_title}` deactivated!\n```\n{}\n```",
-        changes.join("\n")
-    ))
-    .await?;
+    ctx.defer().await?;
+
+    // Falls back to each player's other active games before restoring their original nickname.
+    outcomes.extend(nickname::settle_all(&ctx, &ctx.data().pool, guild_id, released).await);
+
+    let report = nickname_report(ctx, format!("`{game_title}` deactivated!"), &outcomes).await?;
 
     if owner_found {
-        let owner = ctx.guild_id().unwrap().member(ctx, owner_id).await?;
-        let nick_name = strip_character_name(&owner);
-        let why = "[Why?](<https://github.com/Drowrin/eurydice/wiki/Why-is-the-bot-telling-me-to-use-a-nick-command>)";
-
-        if owner.display_name() != nick_name {
-            if owner_id == ctx.author().id {
-                ctx.send(
-                    CreateReply::default()
-                        .content(format!("You need to run `/nick {nick_name}`\n{why}"))
-                        .ephemeral(true),
-                )
-                .await?;
+        let maybe_snapshot = query!(
+            r#"
+            select nickname
+            from nickname_snapshots
+            where guild_id = $1 and user_id = $2
+            "#,
+            guild_id.get() as i64,
+            owner_id.get() as i64,
+        )
+        .fetch_optional(&ctx.data().pool)
+        .await?;
+
+        if let Some(snapshot) = maybe_snapshot {
+            let owner = guild_id.member(ctx, owner_id).await?;
+
+            if owner.nick == snapshot.nickname {
+                nickname::forget(&ctx.data().pool, guild_id, owner_id).await?;
             } else {
-                ctx.say(format!(
-                    "{} needs to run `/nick {nick_name}`\n{why}",
-                    owner_id.mention(),
-                ))
-                .await?;
+                // The owner's nickname can't be changed by the bot, so the snapshot stays until they change it back.
+                let command = match snapshot.nickname {
+                    Some(nick_name) => format!("/nick {nick_name}"),
+                    None => "/nick".to_string(),
+                };
+                let why = "[Why?](<https://github.com/Drowrin/eurydice/wiki/Why-is-the-bot-telling-me-to-use-a-nick-command>)";
+
+                if owner_id == ctx.author().id {
+                    ctx.send(
+                        CreateReply::default()
+                            .content(format!("You need to run `{command}`\n{why}"))
+                            .ephemeral(true),
+                    )
+                    .await?;
+                } else {
+                    ctx.say(format!(
+                        "{} needs to run `{command}`\n{why}",
+                        owner_id.mention(),
+                    ))
+                    .await?;
+                }
             }
         }
     }
 
-    Ok(())
+    retry_failed_nicknames(ctx, report, outcomes).await
 }
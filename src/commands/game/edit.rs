@@ -1,13 +1,13 @@
 use poise::{CreateReply, Modal};
-use serenity::all::{ChannelId, RoleId, UserId};
+use serenity::all::{ChannelId, EditRole, RoleId, UserId};
 use sqlx::query;
 
 use crate::{
     commands::{
         contextual_args,
-        game::{can_manage, game_embed, GameModal},
+        game::{can_manage, edit_role, game_embed, parse_colour, GameModal},
     },
-    Context, Result,
+    Context, Error, Result,
 };
 
 /// Edit the details of a game. Usable by game owners and server moderators.
@@ -17,6 +17,7 @@ pub async fn edit(
     #[description = "The game to edit"]
     #[autocomplete = "crate::autocomplete::game_editable"]
     game: Option<i32>,
+    #[description = "A new colour for the game's role, in hex like #e91e63"] colour: Option<String>,
 ) -> Result<()> {
     let game = contextual_args()
         .game_id_arg(game)
@@ -27,6 +28,16 @@ pub async fn edit(
 
     can_manage(ctx, game).await?;
 
+    let colour = match colour.as_deref().map(parse_colour) {
+        None => None,
+        Some(Some(colour)) => Some(colour),
+        Some(None) => {
+            return Err(Error::Message(
+                "I couldn't read that colour. Write it in hex, like `#e91e63`.".to_string(),
+            ));
+        }
+    };
+
     let maybe_old_game = query!(
         r#"
         select
@@ -49,6 +60,7 @@ pub async fn edit(
         }
     };
 
+    let old_abbreviation = old_game.abbreviation.clone();
     let defaults = GameModal {
         title: old_game.title,
         abbreviation: old_game.abbreviation,
@@ -58,6 +70,9 @@ pub async fn edit(
     let maybe_game_data = GameModal::execute_with_defaults(ctx, defaults).await?;
 
     if let Some(game_data) = maybe_game_data {
+        let abbreviation = game_data.abbreviation.clone();
+        let abbreviation_changed = abbreviation != old_abbreviation;
+
         let record = query!(
             r#"
             update games as g set
@@ -68,11 +83,12 @@ pub async fn edit(
             where id = $1 and guild_id = $2
             returning
                 created_at,
+                active_since,
                 role_id,
                 owner_id,
                 main_channel_id,
                 (select abbreviation from systems where id = g.system_id) as "system",
-                (select user_id from players where game_id = g.id) as "players"
+                array(select user_id from players where game_id = g.id) as "players!"
             "#,
             game,
             ctx.guild_id().unwrap().get() as i64,
@@ -84,6 +100,8 @@ pub async fn edit(
         .fetch_one(&ctx.data().pool)
         .await?;
 
+        let role_id = RoleId::from(record.role_id as u64);
+
         ctx.send(
             CreateReply::default().content("Game updated!").embed(
                 game_embed()
@@ -93,7 +111,8 @@ pub async fn edit(
                     .image(game_data.image)
                     .system(record.system)
                     .created_at(record.created_at)
-                    .role_id(RoleId::from(record.role_id as u64))
+                    .active_since(record.active_since)
+                    .role_id(role_id)
                     .channel_id(record.main_channel_id.map(|c| ChannelId::from(c as u64)))
                     .owner_id(UserId::from(record.owner_id as u64))
                     .players(
@@ -107,6 +126,17 @@ pub async fn edit(
             ),
         )
         .await?;
+
+        // The role is named after the abbreviation, so keep it matching.
+        if abbreviation_changed || colour.is_some() {
+            let mut role = EditRole::new()
+                .name(abbreviation)
+                .audit_log_reason("Game edited");
+            if let Some(colour) = colour {
+                role = role.colour(colour);
+            }
+            edit_role(ctx, role_id, role).await?;
+        }
     }
 
     Ok(())
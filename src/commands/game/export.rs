@@ -0,0 +1,49 @@
+use poise::CreateReply;
+use serenity::all::CreateAttachment;
+
+use crate::{
+    bundle,
+    commands::{contextual_args, game::can_manage},
+    Context, Result,
+};
+
+/// Download this game as JSON and as a Markdown summary. Usable by game owners and server moderators.
+#[poise::command(slash_command, ephemeral)]
+pub async fn export(
+    ctx: Context<'_>,
+    #[description = "The game to export"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    let bundle = bundle::load(&ctx.data().pool, ctx.guild_id().unwrap(), game).await?;
+    let json = serde_json::to_vec_pretty(&bundle).map_err(eyre::Error::from)?;
+    let markdown = bundle::markdown(&bundle);
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Exported `{}`. Use `/game import` with the JSON file to restore it.",
+                bundle.game.title
+            ))
+            .attachment(CreateAttachment::bytes(
+                json,
+                format!("{}.json", bundle.game.abbreviation),
+            ))
+            .attachment(CreateAttachment::bytes(
+                markdown,
+                format!("{}.md", bundle.game.abbreviation),
+            )),
+    )
+    .await?;
+
+    Ok(())
+}
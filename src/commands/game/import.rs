@@ -0,0 +1,342 @@
+use std::collections::{HashMap, HashSet};
+
+use poise::CreateReply;
+use serenity::all::{Attachment, ChannelId, CreateEmbed, Mentionable, UserId};
+use sqlx::{query, types::Json};
+
+use crate::{
+    bundle::{self, Bundle},
+    commands::{
+        confirmation_buttons,
+        game::{insert_game, NewGame},
+    },
+    nickname::failure_reason,
+    sync, Context, Error, Result,
+};
+
+fn bullets(lines: &[String]) -> String {
+    let text = lines
+        .iter()
+        .map(|line| format!("- {line}"))
+        .collect::<Vec<String>>()
+        .join("\n");
+    // Embed fields can only hold so much.
+    if text.chars().count() > 1024 {
+        format!("{}…", text.chars().take(1000).collect::<String>())
+    } else {
+        text
+    }
+}
+
+/// Recreate a game from a JSON bundle made by `/game export`. Usable by anyone.
+#[poise::command(slash_command, ephemeral)]
+pub async fn import(
+    ctx: Context<'_>,
+    #[description = "The JSON file from `/game export`"] file: Attachment,
+    #[description = "Use a different title for the game"]
+    #[min_length = 3]
+    #[max_length = 100]
+    title: Option<String>,
+    #[description = "Use a different abbreviation for the game"]
+    #[min_length = 3]
+    #[max_length = 32]
+    abbreviation: Option<String>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let mut bundle: Bundle = serde_json::from_slice(&file.download().await?).map_err(|e| {
+        Error::Message(format!(
+            "That doesn't look like a file from `/game export`: {e}"
+        ))
+    })?;
+
+    if let Some(title) = title {
+        bundle.game.title = title;
+    }
+    if let Some(abbreviation) = abbreviation {
+        bundle.game.abbreviation = abbreviation;
+    }
+
+    let guild_id = ctx.guild_id().unwrap();
+
+    let games = query!(
+        r#"
+        select title, abbreviation
+        from games
+        where guild_id = $1
+        "#,
+        guild_id.get() as i64,
+    )
+    .fetch_all(&ctx.data().pool)
+    .await?;
+
+    let conflicts = bundle::conflicts(
+        &bundle,
+        &games.iter().map(|g| g.title.clone()).collect(),
+        &games.iter().map(|g| g.abbreviation.clone()).collect(),
+    );
+
+    if !conflicts.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content("Nothing was imported.")
+                .embed(
+                    CreateEmbed::new()
+                        .title(format!("Importing {}", bundle.game.title))
+                        .field("Conflicts", bullets(&conflicts), false),
+                ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let members: HashSet<UserId> = sync::guild_members(ctx.http(), guild_id)
+        .await?
+        .into_iter()
+        .map(|m| m.user.id)
+        .collect();
+
+    let taken_channels: HashSet<ChannelId> = query!(
+        r#"
+        select main_channel_id as "channel_id!"
+        from games
+        where guild_id = $1 and main_channel_id is not null
+        union
+        select channel_id
+        from game_channels
+        where guild_id = $1
+        "#,
+        guild_id.get() as i64,
+    )
+    .fetch_all(&ctx.data().pool)
+    .await?
+    .into_iter()
+    .map(|c| ChannelId::from(c.channel_id as u64))
+    .collect();
+
+    let available_channels: HashSet<ChannelId> = guild_id
+        .channels(ctx)
+        .await?
+        .into_keys()
+        .filter(|c| !taken_channels.contains(c))
+        .collect();
+
+    let changes = bundle::remap(&mut bundle, ctx.author().id, &members, &available_channels);
+
+    let mut report = CreateEmbed::new()
+        .title(format!("Importing {}", bundle.game.title))
+        .field(
+            "Will create",
+            bullets(&[
+                format!(
+                    "The game `{}` with a new role `{}`",
+                    bundle.game.title, bundle.game.abbreviation
+                ),
+                format!("{} characters", bundle.characters.len()),
+                format!("{} players", bundle.players.len()),
+            ]),
+            false,
+        );
+    if !changes.is_empty() {
+        report = report.field("Changes", bullets(&changes), false);
+    }
+
+    let confirmed = confirmation_buttons()
+        .ctx(&ctx)
+        .reply(CreateReply::default().embed(report))
+        .confirm_label("Import")
+        .call()
+        .await?;
+
+    if !confirmed {
+        ctx.say("Nothing was imported.").await?;
+        return Ok(());
+    }
+
+    let system = match &bundle.system {
+        Some(system) => Some(
+            query!(
+                r#"
+                with existing as (
+                    select id
+                    from systems
+                    where guild_id = $1 and (title = $2 or abbreviation = $3)
+                    limit 1
+                ),
+                created as (
+                    insert
+                    into systems
+                        (
+                            guild_id, title, abbreviation, description, image, link,
+                            fields, resolver
+                        )
+                    select
+                        $1, $2, $3, $4, $5, $6, $7, $8
+                    where not exists (select 1 from existing)
+                    returning id
+                )
+                select id as "id!" from existing
+                union all
+                select id from created
+                "#,
+                guild_id.get() as i64,
+                system.title,
+                system.abbreviation,
+                system.description,
+                system.image,
+                system.link,
+                Json(&system.fields) as _,
+                system.resolver,
+            )
+            .fetch_one(&ctx.data().pool)
+            .await?
+            .id,
+        ),
+        None => None,
+    };
+
+    let new_game = insert_game()
+        .ctx(&ctx)
+        .title(&bundle.game.title)
+        .abbreviation(&bundle.game.abbreviation)
+        .maybe_description(bundle.game.description.as_deref())
+        .maybe_image(bundle.game.image.as_deref())
+        .maybe_system(system)
+        .maybe_channel(bundle.game.main_channel_id)
+        .maybe_nickname_template(bundle.game.nickname_template.as_deref())
+        .call()
+        .await?;
+
+    if let Err(e) = fill(ctx, &bundle, &new_game).await {
+        // Take the half-made game back out, so the import can simply be tried again.
+        query!(
+            r#"
+            delete
+            from games
+            where id = $1
+            "#,
+            new_game.id,
+        )
+        .execute(&ctx.data().pool)
+        .await?;
+        guild_id.delete_role(ctx, new_game.role_id).await?;
+        return Err(e);
+    }
+
+    let mut problems = vec![];
+    for player in &bundle.players {
+        if let Err(e) = ctx
+            .http()
+            .add_member_role(guild_id, player.user_id, new_game.role_id, None)
+            .await
+        {
+            problems.push(format!(
+                "I couldn't give {} the role: {}",
+                player.user_id.mention(),
+                failure_reason(&e.into())
+            ));
+        }
+    }
+
+    let mut content = format!(
+        "Imported `{}` with {} characters and {} players!",
+        bundle.game.title,
+        bundle.characters.len(),
+        bundle.players.len()
+    );
+    if !problems.is_empty() {
+        content = format!("{content}\n{}", problems.join("\n"));
+    }
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+/// Add everything in the bundle to a newly created game, all at once or not at all.
+async fn fill(ctx: Context<'_>, bundle: &Bundle, new_game: &NewGame) -> Result<()> {
+    let guild_id = ctx.guild_id().unwrap().get() as i64;
+    let mut tx = ctx.data().pool.begin().await?;
+
+    query!(
+        r#"
+        update games
+        set open = $2
+        where id = $1
+        "#,
+        new_game.id,
+        bundle.game.open,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for channel in &bundle.game.channels {
+        query!(
+            r#"
+            insert
+            into game_channels
+                (channel_id, game_id, guild_id, purpose)
+            values
+                ($1, $2, $3, $4)
+            "#,
+            channel.channel_id.get() as i64,
+            new_game.id,
+            guild_id,
+            channel.purpose,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let mut character_ids = HashMap::new();
+    for character in &bundle.characters {
+        let id = query!(
+            r#"
+            insert
+            into characters
+                (
+                    guild_id, author_id, game_id,
+                    name, pronouns, description, image, fields
+                )
+            values
+                ($1, $2, $3, $4, $5, $6, $7, $8)
+            returning id
+            "#,
+            guild_id,
+            character.author_id.get() as i64,
+            new_game.id,
+            character.name,
+            character.pronouns,
+            character.description,
+            character.image,
+            Json(&character.fields) as _,
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .id;
+        character_ids.insert(&character.name, id);
+    }
+
+    for player in &bundle.players {
+        query!(
+            r#"
+            insert
+            into players
+                (user_id, game_id, character_id)
+            values
+                ($1, $2, $3)
+            "#,
+            player.user_id.get() as i64,
+            new_game.id,
+            player
+                .character
+                .as_ref()
+                .and_then(|c| character_ids.get(c).copied()),
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use serenity::all::{Mentionable, UserId};
+use sqlx::query;
+
+use crate::{commands::contextual_args, events::notify, Context, Error, Result};
+
+/// Join an open game as a player. Usable by everyone.
+#[poise::command(slash_command)]
+pub async fn join(
+    ctx: Context<'_>,
+    #[description = "The game to join"]
+    #[autocomplete = "crate::autocomplete::game_open"]
+    game: Option<i32>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    let game_data = query!(
+        r#"
+        select title, owner_id, role_id, open
+        from games
+        where guild_id = $1 and id = $2
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        game,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?;
+
+    if !game_data.open {
+        return Err(Error::Message(format!(
+            "`{}` isn't open. Ask the game owner for an invite!",
+            game_data.title
+        )));
+    }
+
+    if game_data.owner_id == ctx.author().id.get() as i64 {
+        return Err(Error::Message("You already own that game.".to_string()));
+    }
+
+    let mut tx = ctx.data().pool.begin().await?;
+
+    let joined = query!(
+        r#"
+        insert
+        into players (user_id, game_id)
+        values ($1, $2)
+        on conflict do nothing
+        "#,
+        ctx.author().id.get() as i64,
+        game,
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if joined == 0 {
+        return Err(Error::Message("You're already in that game.".to_string()));
+    }
+
+    query!(
+        r#"
+        delete
+        from invites
+        where game_id = $1 and user_id = $2
+        "#,
+        game,
+        ctx.author().id.get() as i64,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // Dropping the transaction without committing rolls everything back.
+    if ctx
+        .author_member()
+        .await
+        .unwrap()
+        .add_role(ctx, game_data.role_id as u64)
+        .await
+        .is_err()
+    {
+        return Err(Error::Message(
+            "I couldn't give you the game's role, so you weren't added. Please ask the game owner to check that I can manage roles."
+                .to_string(),
+        ));
+    }
+
+    tx.commit().await?;
+
+    notify(
+        ctx.serenity_context(),
+        UserId::from(game_data.owner_id as u64),
+        format!("{} joined `{}`!", ctx.author().mention(), game_data.title),
+    )
+    .await;
+
+    ctx.say(format!(
+        "{} joined `{}`!",
+        ctx.author().mention(),
+        game_data.title
+    ))
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use serenity::all::{Mentionable, UserId};
+use sqlx::query;
+
+use crate::{commands::contextual_args, events::notify, nickname, Context, Error, Result};
+
+/// Leave a game you're playing in, releasing your character. Usable by the game's players.
+#[poise::command(slash_command)]
+pub async fn leave(
+    ctx: Context<'_>,
+    #[description = "The game to leave"]
+    #[autocomplete = "crate::autocomplete::game_playing"]
+    game: Option<i32>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    let mut tx = ctx.data().pool.begin().await?;
+
+    let record = query!(
+        r#"
+        delete
+        from players
+        where user_id = $1 and game_id = $2
+        returning
+            (select title from games where id = $2) as "title!",
+            (select owner_id from games where id = $2) as "owner_id!",
+            (select role_id from games where id = $2) as "role_id!",
+            (select name from characters where id = character_id) as "character"
+        "#,
+        ctx.author().id.get() as i64,
+        game,
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| Error::Message("You aren't a player in that game.".to_string()))?;
+
+    // Dropping the transaction without committing rolls everything back.
+    if ctx
+        .author_member()
+        .await
+        .unwrap()
+        .remove_role(ctx, record.role_id as u64)
+        .await
+        .is_err()
+    {
+        return Err(Error::Message(
+            "I couldn't take away the game's role, so you're still in the game. Please ask the game owner to check that I can manage roles."
+                .to_string(),
+        ));
+    }
+
+    tx.commit().await?;
+
+    let released = match &record.character {
+        Some(character) => format!(" `{character}` was released."),
+        None => String::new(),
+    };
+
+    notify(
+        ctx.serenity_context(),
+        UserId::from(record.owner_id as u64),
+        format!(
+            "{} left `{}`.{released}",
+            ctx.author().mention(),
+            record.title
+        ),
+    )
+    .await;
+
+    let mut content = format!("You left `{}`.{released}", record.title);
+    let refreshed = nickname::refresh(ctx, &ctx.data().pool, game, ctx.author().id).await;
+    if let Some(note) = nickname::describe(&refreshed) {
+        content = format!("{content}\n{note}");
+    }
+    ctx.say(content).await?;
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use serenity::all::User;
+use sqlx::query;
+
+use crate::{commands::paginate, Context, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum GameStatus {
+    Active,
+    Inactive,
+    Open,
+}
+
+/// List the games in this server. Usable by everyone.
+#[poise::command(slash_command)]
+pub async fn list(
+    ctx: Context<'_>,
+    #[description = "Only show games using this system"]
+    #[autocomplete = "crate::autocomplete::system"]
+    system: Option<i32>,
+    #[description = "Only show games owned by this user"] owner: Option<User>,
+    #[description = "Only show games this user is playing in"] player: Option<User>,
+    #[description = "Only show games you own or play in"] mine: Option<bool>,
+    #[description = "Only show games with this status"] status: Option<GameStatus>,
+) -> Result<()> {
+    let games = query!(
+        r#"
+        select
+            abbreviation, title,
+            (select abbreviation from systems where id = g.system_id) as "system",
+            (select count(*) from players where game_id = g.id) as "players!",
+            active_since is not null as "active!",
+            open
+        from games as g
+        where
+            guild_id = $1
+            and
+            ($2::int is null or system_id = $2)
+            and
+            ($3::bigint is null or owner_id = $3)
+            and
+            (
+                $4::bigint is null
+                or
+                exists (
+                    select 1
+                    from players
+                    where
+                        game_id = g.id
+                        and
+                        user_id = $4
+                )
+            )
+            and
+            (
+                not $5
+                or
+                owner_id = $6
+                or
+                exists (
+                    select 1
+                    from players
+                    where
+                        game_id = g.id
+                        and
+                        user_id = $6
+                )
+            )
+            and
+            (
+                $7::text is null
+                or
+                ($7 = 'active' and active_since is not null)
+                or
+                ($7 = 'inactive' and active_since is null)
+                or
+                ($7 = 'open' and open)
+            )
+        order by title
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        system,
+        owner.map(|u| u.id.get() as i64),
+        player.map(|u| u.id.get() as i64),
+        mine.unwrap_or_default(),
+        ctx.author().id.get() as i64,
+        status.map(|s| match s {
+            GameStatus::Active => "active",
+            GameStatus::Inactive => "inactive",
+            GameStatus::Open => "open",
+        }),
+    )
+    .fetch_all(&ctx.data().pool)
+    .await?;
+
+    let lines = games
+        .into_iter()
+        .map(|game| {
+            let mut details = vec![];
+            if let Some(system) = game.system {
+                details.push(system);
+            }
+            details.push(match game.players {
+                1 => "1 player".to_string(),
+                n => format!("{n} players"),
+            });
+            if game.active {
+                details.push("Active".to_string());
+            }
+            if game.open {
+                details.push("Open".to_string());
+            }
+            format!(
+                "`{}` **{}** · {}",
+                game.abbreviation,
+                game.title,
+                details.join(" · ")
+            )
+        })
+        .collect();
+
+    paginate()
+        .ctx(&ctx)
+        .title("Games")
+        .lines(lines)
+        .empty_message("No games found!")
+        .call()
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use poise::CreateReply;
+use serenity::all::UserId;
+use sqlx::query;
+
+use crate::{
+    commands::{confirmation_buttons, contextual_args, game::can_manage},
+    nickname::{preview_embed, validate},
+    Context, Error, Result,
+};
+
+/// Set the nickname template for this game. Usable by game owners and server moderators.
+#[poise::command(slash_command, ephemeral)]
+pub async fn nickname(
+    ctx: Context<'_>,
+    #[description = "Template using {character} and {player}. Leave empty to use the server default"]
+    #[max_length = 100]
+    template: Option<String>,
+    #[description = "The game to set the nickname template of"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    if let Some(template) = &template {
+        validate(template).map_err(|e| Error::Message(e.to_string()))?;
+    }
+
+    let record = query!(
+        r#"
+        select
+            title,
+            coalesce(
+                $2,
+                (select nickname_template from guild_settings where guild_id = g.guild_id)
+            ) as "template"
+        from games as g
+        where id = $1
+        "#,
+        game,
+        template,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?;
+    let preview = record
+        .template
+        .unwrap_or_else(|| crate::nickname::DEFAULT_TEMPLATE.to_string());
+
+    let players = query!(
+        r#"
+        select
+            user_id,
+            c.name
+        from players as p
+        join characters as c on c.id = p.character_id
+        where p.game_id = $1
+        limit 5
+        "#,
+        game,
+    )
+    .fetch_all(&ctx.data().pool)
+    .await?;
+
+    let mut samples = vec![];
+    for player in players {
+        // Players who have left the server can't be shown, but shouldn't stop the preview.
+        let Ok(member) = ctx
+            .guild_id()
+            .unwrap()
+            .member(ctx, UserId::from(player.user_id as u64))
+            .await
+        else {
+            continue;
+        };
+        samples.push((player.name, member.display_name().to_string()));
+    }
+    if samples.is_empty() {
+        samples.push((
+            "Aloysius Thorne".to_string(),
+            ctx.author_member()
+                .await
+                .unwrap()
+                .display_name()
+                .to_string(),
+        ));
+    }
+
+    let confirmed = confirmation_buttons()
+        .ctx(&ctx)
+        .reply(
+            CreateReply::default()
+                .content(format!(
+                    "Save this nickname template for `{}`?",
+                    record.title
+                ))
+                .embed(preview_embed(&preview, &samples)),
+        )
+        .confirm_label("Save")
+        .call()
+        .await?;
+
+    if !confirmed {
+        ctx.say("Nickname template was not changed.").await?;
+        return Ok(());
+    }
+
+    query!(
+        r#"
+        update games
+        set nickname_template = $2
+        where id = $1
+        "#,
+        game,
+        template,
+    )
+    .execute(&ctx.data().pool)
+    .await?;
+
+    ctx.say(format!(
+        "Nickname template of `{}` set to `{preview}`.",
+        record.title
+    ))
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use sqlx::query;
+
+use crate::{
+    commands::{contextual_args, game::can_manage},
+    Context, Error, Result,
+};
+
+/// Let anyone in the server join this game themselves. Usable by game owners and server moderators.
+#[poise::command(slash_command)]
+pub async fn open(
+    ctx: Context<'_>,
+    #[description = "The game to open"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+    #[description = "Set to false to close the game again"] open: Option<bool>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    let open = open.unwrap_or(true);
+
+    let title = query!(
+        r#"
+        update games
+        set open = $2
+        where id = $1 and guild_id = $3
+        returning title
+        "#,
+        game,
+        open,
+        ctx.guild_id().unwrap().get() as i64,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?
+    .ok_or(Error::NotFound)?
+    .title;
+
+    if open {
+        ctx.say(format!(
+            "`{title}` is open! Anyone can join with `/game join`."
+        ))
+        .await?;
+    } else {
+        ctx.say(format!(
+            "`{title}` is closed. Players can only be added by the game owner."
+        ))
+        .await?;
+    }
+
+    Ok(())
+}
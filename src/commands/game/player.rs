@@ -1,12 +1,25 @@
 use crate::{Context, Result};
 
 mod add;
+mod add_many;
+mod invite;
 mod remove;
+mod remove_all;
+mod revoke;
+
+pub use invite::respond as respond_to_invite;
 
 #[poise::command(
     slash_command,
     subcommand_required,
-    subcommands("add::add", "remove::remove"),
+    subcommands(
+        "add::add",
+        "add_many::add_many",
+        "remove::remove",
+        "remove_all::remove_all",
+        "invite::invite",
+        "revoke::revoke"
+    ),
     guild_only
 )]
 pub async fn player(_: Context<'_>) -> Result<()> {
@@ -3,7 +3,7 @@ use sqlx::query;
 
 use crate::{
     commands::{contextual_args, game::can_manage},
-    Context, Result,
+    nickname, Context, Result,
 };
 
 /// Add a player to this game. Usable by game owners and server moderators.
@@ -59,12 +59,30 @@ pub async fn add(
     match record {
         Ok(record) => {
             user.add_role(ctx, record.role_id.unwrap() as u64).await?;
-            ctx.say(format!(
+
+            // They don't need an invite anymore.
+            query!(
+                r#"
+                delete
+                from invites
+                where game_id = $1 and user_id = $2
+                "#,
+                game,
+                user.user.id.get() as i64,
+            )
+            .execute(&ctx.data().pool)
+            .await?;
+
+            let mut content = format!(
                 "Player {} added to `{}`!",
                 user.mention(),
                 record.title.unwrap()
-            ))
-            .await?;
+            );
+            let refreshed = nickname::refresh(ctx, &ctx.data().pool, game, user.user.id).await;
+            if let Some(note) = nickname::describe(&refreshed) {
+                content = format!("{content}\n{note}");
+            }
+            ctx.say(content).await?;
         }
         Err(sqlx::Error::Database(_)) => {
             ctx.say(format!("{} is already in that game.", user.mention()))
@@ -0,0 +1,149 @@
+use serenity::all::{Member, Mentionable, Role, UserId};
+use sqlx::query;
+
+use crate::{
+    commands::{contextual_args, game::can_manage, paginate},
+    nickname::failure_reason,
+    sync, Context, Error, Result,
+};
+
+/// Add several players to this game at once. Usable by game owners and server moderators.
+#[poise::command(slash_command, rename = "add-many")]
+#[allow(clippy::too_many_arguments)]
+pub async fn add_many(
+    ctx: Context<'_>,
+    #[description = "The game to add players to"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+    #[description = "Add every member with this role"] role: Option<Role>,
+    #[description = "A user to add to the game"] user_1: Option<Member>,
+    #[description = "A user to add to the game"] user_2: Option<Member>,
+    #[description = "A user to add to the game"] user_3: Option<Member>,
+    #[description = "A user to add to the game"] user_4: Option<Member>,
+    #[description = "A user to add to the game"] user_5: Option<Member>,
+    #[description = "A user to add to the game"] user_6: Option<Member>,
+    #[description = "A user to add to the game"] user_7: Option<Member>,
+    #[description = "A user to add to the game"] user_8: Option<Member>,
+    #[description = "A user to add to the game"] user_9: Option<Member>,
+    #[description = "A user to add to the game"] user_10: Option<Member>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    let mut users: Vec<UserId> = [
+        user_1, user_2, user_3, user_4, user_5, user_6, user_7, user_8, user_9, user_10,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|m| m.user.id)
+    .collect();
+
+    if let Some(role) = &role {
+        ctx.defer().await?;
+
+        users.extend(
+            sync::guild_members(ctx.http(), ctx.guild_id().unwrap())
+                .await?
+                .into_iter()
+                .filter(|m| m.roles.contains(&role.id) && !m.user.bot)
+                .map(|m| m.user.id),
+        );
+    }
+
+    users.sort();
+    users.dedup();
+
+    if users.is_empty() {
+        return Err(Error::Message(
+            "Choose some users or a role to add.".to_string(),
+        ));
+    }
+
+    let game_data = query!(
+        r#"
+        select title, owner_id, role_id
+        from games
+        where guild_id = $1 and id = $2
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        game,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?;
+
+    let mut lines = vec![];
+
+    for user_id in users {
+        if user_id.get() as i64 == game_data.owner_id {
+            lines.push(format!("➖ {} skipped: game owner", user_id.mention()));
+            continue;
+        }
+
+        let added = query!(
+            r#"
+            insert
+            into players (user_id, game_id)
+            values ($1, $2)
+            on conflict do nothing
+            "#,
+            user_id.get() as i64,
+            game,
+        )
+        .execute(&ctx.data().pool)
+        .await?
+        .rows_affected();
+
+        if added == 0 {
+            lines.push(format!(
+                "➖ {} skipped: already in the game",
+                user_id.mention()
+            ));
+            continue;
+        }
+
+        query!(
+            r#"
+            delete
+            from invites
+            where game_id = $1 and user_id = $2
+            "#,
+            game,
+            user_id.get() as i64,
+        )
+        .execute(&ctx.data().pool)
+        .await?;
+
+        match ctx
+            .http()
+            .add_member_role(
+                ctx.guild_id().unwrap(),
+                user_id,
+                (game_data.role_id as u64).into(),
+                None,
+            )
+            .await
+        {
+            Ok(_) => lines.push(format!("✅ {} added", user_id.mention())),
+            Err(e) => lines.push(format!(
+                "❌ {} added, but I couldn't give them the role: {}",
+                user_id.mention(),
+                failure_reason(&e.into())
+            )),
+        }
+    }
+
+    paginate()
+        .ctx(&ctx)
+        .title(&format!("Players added to {}", game_data.title))
+        .lines(lines)
+        .call()
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,319 @@
+use poise::CreateReply;
+use serenity::all::{
+    ButtonStyle, ComponentInteraction, CreateActionRow, CreateButton, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, GuildId, Member, Mentionable, UserId,
+};
+use sqlx::query;
+
+use crate::{
+    commands::{contextual_args, game::can_manage},
+    events::notify,
+    nickname, Context, Data, Error, Result,
+};
+
+fn invite_buttons(invite: i32) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("invite:accept:{invite}"))
+            .label("Accept")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("invite:decline:{invite}"))
+            .label("Decline")
+            .style(ButtonStyle::Secondary),
+    ])]
+}
+
+/// Invite a user to play in this game. Usable by game owners and server moderators.
+#[poise::command(slash_command)]
+pub async fn invite(
+    ctx: Context<'_>,
+    #[description = "The user to invite to the game"] user: Member,
+    #[description = "The game to invite a player to"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    let game_data = query!(
+        r#"
+        select
+            title, owner_id,
+            exists (
+                select 1
+                from players
+                where game_id = g.id and user_id = $3
+            ) as "is_player!"
+        from games as g
+        where guild_id = $1 and id = $2
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        game,
+        user.user.id.get() as i64,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?;
+
+    if game_data.owner_id == user.user.id.get() as i64 {
+        return Err(Error::Message(
+            "Can't invite game owner as a player.".to_string(),
+        ));
+    }
+
+    if game_data.is_player {
+        return Err(Error::Message(format!(
+            "{} is already in that game.",
+            user.mention()
+        )));
+    }
+
+    // Inviting someone again just brings their invite up to date.
+    let invite = query!(
+        r#"
+        insert
+        into invites
+            (game_id, guild_id, user_id, invited_by)
+        values
+            ($1, $2, $3, $4)
+        on conflict (game_id, user_id)
+        do update set
+            invited_by = excluded.invited_by
+        returning id
+        "#,
+        game,
+        ctx.guild_id().unwrap().get() as i64,
+        user.user.id.get() as i64,
+        ctx.author().id.get() as i64,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?
+    .id;
+
+    let guild_name = ctx.guild().unwrap().name.clone();
+    let dm = user
+        .user
+        .id
+        .direct_message(
+            ctx,
+            CreateMessage::new()
+                .content(format!(
+                    "{} invited you to play in `{}` on **{guild_name}**.",
+                    ctx.author().mention(),
+                    game_data.title,
+                ))
+                .components(invite_buttons(invite)),
+        )
+        .await;
+
+    match dm {
+        Ok(_) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!(
+                        "Invited {} to `{}`!",
+                        user.mention(),
+                        game_data.title
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+        }
+        // Their DMs are closed, so ask them here instead.
+        Err(_) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!(
+                        "{}, {} invited you to play in `{}`.",
+                        user.mention(),
+                        ctx.author().mention(),
+                        game_data.title,
+                    ))
+                    .components(invite_buttons(invite)),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Close the invite message, replacing its content.
+async fn close(
+    ctx: &serenity::all::Context,
+    press: &ComponentInteraction,
+    content: String,
+) -> Result<()> {
+    press
+        .create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Handle the invited user's answer to an invite.
+pub async fn respond(
+    ctx: &serenity::all::Context,
+    data: &Data,
+    press: &ComponentInteraction,
+    action: &str,
+    invite: i32,
+) -> Result<()> {
+    let maybe_invite = query!(
+        r#"
+        select
+            user_id,
+            (select title from games where id = game_id) as "title!",
+            (select owner_id from games where id = game_id) as "owner_id!"
+        from invites
+        where id = $1
+        "#,
+        invite,
+    )
+    .fetch_optional(&data.pool)
+    .await?;
+
+    let invite_data = match maybe_invite {
+        Some(invite_data) => invite_data,
+        None => {
+            return close(
+                ctx,
+                press,
+                "This invite is no longer available.".to_string(),
+            )
+            .await
+        }
+    };
+
+    if press.user.id.get() as i64 != invite_data.user_id {
+        return Err(Error::Message("This invite isn't for you.".to_string()));
+    }
+
+    let owner_id = UserId::from(invite_data.owner_id as u64);
+
+    match action {
+        "accept" => accept(ctx, data, press, invite).await,
+        "decline" => {
+            query!(
+                r#"
+                delete
+                from invites
+                where id = $1
+                "#,
+                invite,
+            )
+            .execute(&data.pool)
+            .await?;
+
+            notify(
+                ctx,
+                owner_id,
+                format!(
+                    "{} declined your invite to `{}`.",
+                    press.user.mention(),
+                    invite_data.title
+                ),
+            )
+            .await;
+
+            close(
+                ctx,
+                press,
+                format!("You declined the invite to `{}`.", invite_data.title),
+            )
+            .await
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Add the invited user to the game, all at once or not at all.
+async fn accept(
+    ctx: &serenity::all::Context,
+    data: &Data,
+    press: &ComponentInteraction,
+    invite: i32,
+) -> Result<()> {
+    let mut tx = data.pool.begin().await?;
+
+    let invite_data = query!(
+        r#"
+        delete
+        from invites
+        where id = $1
+        returning game_id, guild_id, user_id
+        "#,
+        invite,
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| Error::Message("This invite is no longer available.".to_string()))?;
+
+    let game_data = query!(
+        r#"
+        insert
+        into players (user_id, game_id)
+        values ($1, $2)
+        on conflict do nothing
+        returning
+            (select title from games where id = $2) as "title!",
+            (select owner_id from games where id = $2) as "owner_id!",
+            (select role_id from games where id = $2) as "role_id!"
+        "#,
+        invite_data.user_id,
+        invite_data.game_id,
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| Error::Message("You're already in that game.".to_string()))?;
+
+    // Dropping the transaction without committing rolls everything back.
+    if ctx
+        .http
+        .add_member_role(
+            GuildId::from(invite_data.guild_id as u64),
+            press.user.id,
+            (game_data.role_id as u64).into(),
+            Some("Accepted game invite"),
+        )
+        .await
+        .is_err()
+    {
+        return Err(Error::Message(
+            "I couldn't give you the game's role, so you weren't added. Please ask the game owner to check that I can manage roles."
+                .to_string(),
+        ));
+    }
+
+    tx.commit().await?;
+
+    notify(
+        ctx,
+        UserId::from(game_data.owner_id as u64),
+        format!(
+            "{} accepted your invite to `{}`!",
+            press.user.mention(),
+            game_data.title
+        ),
+    )
+    .await;
+
+    let mut content = format!("You joined `{}`!", game_data.title);
+    let refreshed = nickname::refresh(ctx, &data.pool, invite_data.game_id, press.user.id).await;
+    if let Some(note) = nickname::describe(&refreshed) {
+        content = format!("{content}\n{note}");
+    }
+
+    close(ctx, press, content).await
+}
@@ -3,7 +3,7 @@ use sqlx::query;
 
 use crate::{
     commands::{contextual_args, game::can_manage},
-    Context, Result,
+    nickname, Context, Result,
 };
 
 /// Remove a player from this game. Usable by game owners and server moderators.
@@ -43,12 +43,17 @@ pub async fn remove(
         Ok(record) => {
             user.remove_role(ctx, record.role_id.unwrap() as u64)
                 .await?;
-            ctx.say(format!(
+
+            let mut content = format!(
                 "Player {} removed from {}!",
                 user.mention(),
                 record.title.unwrap()
-            ))
-            .await?;
+            );
+            let refreshed = nickname::refresh(ctx, &ctx.data().pool, game, user.user.id).await;
+            if let Some(note) = nickname::describe(&refreshed) {
+                content = format!("{content}\n{note}");
+            }
+            ctx.say(content).await?;
         }
         Err(sqlx::Error::RowNotFound) => {
             ctx.say(format!(
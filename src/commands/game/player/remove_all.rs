@@ -0,0 +1,115 @@
+use poise::CreateReply;
+use serenity::all::{Mentionable, UserId};
+use sqlx::query;
+
+use crate::{
+    commands::{confirmation_buttons, contextual_args, game::can_manage, paginate},
+    nickname::{self, failure_reason},
+    Context, Error, Result,
+};
+
+/// Remove every player from this game. Usable by game owners and server moderators.
+#[poise::command(slash_command, rename = "remove-all")]
+pub async fn remove_all(
+    ctx: Context<'_>,
+    #[description = "The game to remove all players from"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    let game_data = query!(
+        r#"
+        select
+            title, role_id,
+            (select count(*) from players where game_id = g.id) as "players!"
+        from games as g
+        where guild_id = $1 and id = $2
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        game,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?;
+
+    if game_data.players == 0 {
+        return Err(Error::Message(format!(
+            "`{}` doesn't have any players.",
+            game_data.title
+        )));
+    }
+
+    let confirmed = confirmation_buttons()
+        .ctx(&ctx)
+        .reply(CreateReply::default().content(format!(
+            "Remove all {} players from `{}`? Their characters will be released.",
+            game_data.players, game_data.title
+        )))
+        .confirm_label("Remove all")
+        .call()
+        .await?;
+
+    if !confirmed {
+        ctx.say("Nobody was removed.").await?;
+        return Ok(());
+    }
+
+    let removed = query!(
+        r#"
+        delete
+        from players
+        where game_id = $1
+        returning user_id
+        "#,
+        game,
+    )
+    .fetch_all(&ctx.data().pool)
+    .await?;
+
+    let mut lines = vec![];
+
+    for player in removed {
+        let user_id = UserId::from(player.user_id as u64);
+
+        let mut line = match ctx
+            .http()
+            .remove_member_role(
+                ctx.guild_id().unwrap(),
+                user_id,
+                (game_data.role_id as u64).into(),
+                None,
+            )
+            .await
+        {
+            Ok(_) => format!("✅ {} removed", user_id.mention()),
+            Err(e) => format!(
+                "❌ {} removed, but I couldn't take their role: {}",
+                user_id.mention(),
+                failure_reason(&e.into())
+            ),
+        };
+
+        let refreshed = nickname::refresh(ctx, &ctx.data().pool, game, user_id).await;
+        if let Some(note) = nickname::describe(&refreshed) {
+            line = format!("{line}. {note}");
+        }
+
+        lines.push(line);
+    }
+
+    paginate()
+        .ctx(&ctx)
+        .title(&format!("Players removed from {}", game_data.title))
+        .lines(lines)
+        .call()
+        .await?;
+
+    Ok(())
+}
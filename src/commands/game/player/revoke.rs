@@ -0,0 +1,58 @@
+use serenity::all::{Member, Mentionable};
+use sqlx::query;
+
+use crate::{
+    commands::{contextual_args, game::can_manage},
+    Context, Error, Result,
+};
+
+/// Revoke a pending invite to this game. Usable by game owners and server moderators.
+#[poise::command(slash_command)]
+pub async fn revoke(
+    ctx: Context<'_>,
+    #[description = "The user whose invite should be revoked"] user: Member,
+    #[description = "The game to revoke an invite to"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    let maybe_revoked = query!(
+        r#"
+        delete
+        from invites
+        where game_id = $1 and user_id = $2
+        returning (select title from games where id = $1) as "title!"
+        "#,
+        game,
+        user.user.id.get() as i64,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?;
+
+    match maybe_revoked {
+        Some(revoked) => {
+            ctx.say(format!(
+                "Revoked {}'s invite to `{}`.",
+                user.mention(),
+                revoked.title
+            ))
+            .await?;
+        }
+        None => {
+            return Err(Error::Message(format!(
+                "{} doesn't have a pending invite to that game.",
+                user.mention()
+            )));
+        }
+    }
+
+    Ok(())
+}
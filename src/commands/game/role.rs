@@ -0,0 +1,88 @@
+use serenity::all::{Attachment, CreateAttachment, EditRole, Mentionable, RoleId};
+use sqlx::query;
+
+use crate::{
+    commands::{
+        contextual_args,
+        game::{can_manage, edit_role, parse_colour},
+    },
+    Context, Error, Result,
+};
+
+/// Change the colour or icon of this game's role. Usable by game owners and server moderators.
+#[poise::command(slash_command, ephemeral)]
+pub async fn role(
+    ctx: Context<'_>,
+    #[description = "The game whose role to change"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+    #[description = "The role's colour, in hex like #e91e63"] colour: Option<String>,
+    #[description = "An image for the role's icon. Needs server boosts"] icon: Option<Attachment>,
+    #[description = "An emoji for the role's icon, instead of an image"] emoji: Option<String>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    if colour.is_none() && icon.is_none() && emoji.is_none() {
+        return Err(Error::Message(
+            "Choose a `colour`, `icon`, or `emoji` to change.".to_string(),
+        ));
+    }
+
+    if icon.is_some() && emoji.is_some() {
+        return Err(Error::Message(
+            "A role can have an `icon` or an `emoji`, not both.".to_string(),
+        ));
+    }
+
+    let role_id = RoleId::from(
+        query!(
+            r#"
+            select role_id
+            from games
+            where id = $1
+            "#,
+            game,
+        )
+        .fetch_one(&ctx.data().pool)
+        .await?
+        .role_id as u64,
+    );
+
+    ctx.defer_ephemeral().await?;
+
+    let icon = match icon {
+        Some(icon) => Some(CreateAttachment::url(ctx, &icon.url).await?),
+        None => None,
+    };
+
+    let mut role = EditRole::new().audit_log_reason("Game role edited");
+
+    if let Some(colour) = colour {
+        role = role.colour(parse_colour(&colour).ok_or_else(|| {
+            Error::Message(
+                "I couldn't read that colour. Write it in hex, like `#e91e63`.".to_string(),
+            )
+        })?);
+    }
+
+    if let Some(icon) = &icon {
+        role = role.icon(Some(icon));
+    }
+
+    if let Some(emoji) = emoji {
+        role = role.unicode_emoji(Some(emoji));
+    }
+
+    if edit_role(ctx, role_id, role).await? {
+        ctx.say(format!("Updated {}.", role_id.mention())).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use serenity::all::Mentionable;
+use sqlx::query;
+
+use crate::{
+    commands::{
+        contextual_args,
+        game::is_manager,
+        paginate,
+        roll::{logged_rolls, Visibility},
+    },
+    Context, Result,
+};
+
+/// Show the most recent rolls made in this game's channels. Usable by everyone.
+#[poise::command(slash_command, ephemeral)]
+pub async fn rolls(
+    ctx: Context<'_>,
+    #[description = "The game to show rolls for"]
+    #[autocomplete = "crate::autocomplete::game"]
+    game: Option<i32>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    let title = query!(
+        r#"
+        select title
+        from games
+        where id = $1 and guild_id = $2
+        "#,
+        game,
+        ctx.guild_id().unwrap().get() as i64,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?
+    .title;
+
+    let rolls = logged_rolls()
+        .conn(&mut *ctx.data().pool.acquire().await?)
+        .game(game)
+        .viewer(ctx.author().id)
+        .manager(is_manager(ctx, game).await?)
+        .limit(100)
+        .call()
+        .await?;
+
+    let lines = rolls
+        .into_iter()
+        .map(|roll| {
+            let roller = roll.user_id.mention().to_string();
+            let roller = match roll.character {
+                Some(character) => format!("**{character}** ({roller})"),
+                None => roller,
+            };
+            let total = match roll.outcome {
+                Some(outcome) => format!("{} ({outcome})", roll.total),
+                None => roll.total.to_string(),
+            };
+            let hidden = match roll.visibility {
+                Visibility::Public => "",
+                Visibility::Gm => "🔒 ",
+                Visibility::Own => "👁️ ",
+            };
+            format!(
+                "{hidden}**{total}** · `{}` · {roller} <t:{}:R>",
+                roll.expression,
+                roll.rolled_at.timestamp()
+            )
+        })
+        .collect();
+
+    paginate()
+        .ctx(&ctx)
+        .title(&format!("Rolls in {title}"))
+        .lines(lines)
+        .empty_message("No rolls yet! Use `/roll dice` in one of the game's channels.")
+        .call()
+        .await?;
+
+    Ok(())
+}
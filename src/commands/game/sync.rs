@@ -0,0 +1,127 @@
+use poise::CreateReply;
+use serenity::all::{CreateEmbed, Mentionable, UserId};
+use sqlx::query;
+
+use crate::{
+    commands::{contextual_args, game::can_manage},
+    sync::{self, SyncDirection},
+    Context, Error, Result,
+};
+
+fn mentions(users: &[UserId]) -> String {
+    if users.is_empty() {
+        "None".to_string()
+    } else {
+        users
+            .iter()
+            .map(|u| u.mention().to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+/// Compare this game's role with its player list. Usable by game owners and server moderators.
+#[poise::command(slash_command, ephemeral)]
+pub async fn sync(
+    ctx: Context<'_>,
+    #[description = "The game to sync"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+    #[description = "Fix any differences in this direction"] fix: Option<SyncDirection>,
+    #[description = "Keep fixing differences in this direction every hour"] automatic: Option<bool>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    if automatic == Some(true) && fix.is_none() {
+        return Err(Error::Message(
+            "Choose a direction to `fix` in to sync automatically.".to_string(),
+        ));
+    }
+
+    ctx.defer_ephemeral().await?;
+
+    let roster = sync::roster(&ctx.data().pool, game).await?;
+    let members = sync::guild_members(ctx.http(), roster.guild_id).await?;
+    let drift = sync::drift(&roster, &members);
+
+    if let Some(direction) = fix {
+        sync::fix(ctx.http(), &ctx.data().pool, &roster, &drift, direction).await?;
+    }
+
+    let title = match automatic {
+        Some(automatic) => {
+            query!(
+                r#"
+                update games
+                set auto_sync = $2
+                where id = $1
+                returning title
+                "#,
+                game,
+                automatic.then(|| fix.unwrap().as_str()),
+            )
+            .fetch_one(&ctx.data().pool)
+            .await?
+            .title
+        }
+        None => {
+            query!(
+                r#"
+                select title
+                from games
+                where id = $1
+                "#,
+                game,
+            )
+            .fetch_one(&ctx.data().pool)
+            .await?
+            .title
+        }
+    };
+
+    let mut content = match (drift.is_empty(), fix) {
+        (true, _) => format!("`{title}` is already in sync."),
+        (false, Some(_)) => format!("Synced `{title}`."),
+        (false, None) => format!("`{title}` is out of sync. Use `fix` to resolve it."),
+    };
+    match automatic {
+        Some(true) => content.push_str("\nIt will be synced automatically every hour."),
+        Some(false) => content.push_str("\nIt will no longer be synced automatically."),
+        None => {}
+    }
+
+    let mut reply = CreateReply::default().content(content);
+
+    if !drift.is_empty() {
+        reply = reply.embed(
+            CreateEmbed::new()
+                .title("Differences")
+                .field(
+                    "Players without the role",
+                    mentions(&drift.missing_role),
+                    false,
+                )
+                .field(
+                    "Role holders who aren't players",
+                    mentions(&drift.extra_role),
+                    false,
+                )
+                .field(
+                    "Players who left the server",
+                    mentions(&drift.departed),
+                    false,
+                ),
+        );
+    }
+
+    ctx.send(reply).await?;
+
+    Ok(())
+}
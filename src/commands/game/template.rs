@@ -0,0 +1,15 @@
+use crate::{Context, Result};
+
+mod delete;
+mod list;
+mod save;
+
+#[poise::command(
+    slash_command,
+    subcommand_required,
+    subcommands("save::save", "list::list", "delete::delete"),
+    guild_only
+)]
+pub async fn template(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use sqlx::query;
+
+use crate::{Context, Error, Result};
+
+/// Delete a game template. Games already started from it are unaffected. Usable by server moderators.
+#[poise::command(slash_command, required_permissions = "MANAGE_MESSAGES", ephemeral)]
+pub async fn delete(
+    ctx: Context<'_>,
+    #[description = "The template to delete"]
+    #[autocomplete = "crate::autocomplete::template"]
+    template: i32,
+) -> Result<()> {
+    let name = query!(
+        r#"
+        delete
+        from game_templates
+        where id = $1 and guild_id = $2
+        returning name
+        "#,
+        template,
+        ctx.guild_id().unwrap().get() as i64,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?
+    .ok_or(Error::NotFound)?
+    .name;
+
+    ctx.say(format!("Deleted template `{name}`.")).await?;
+
+    Ok(())
+}
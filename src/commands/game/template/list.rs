@@ -0,0 +1,47 @@
+use sqlx::query;
+
+use crate::{commands::paginate, Context, Result};
+
+/// List the game templates in this server. Usable by everyone.
+#[poise::command(slash_command)]
+pub async fn list(ctx: Context<'_>) -> Result<()> {
+    let templates = query!(
+        r#"
+        select
+            name,
+            (select abbreviation from systems where id = t.system_id) as "system",
+            (select count(*) from template_characters where template_id = t.id) as "characters!"
+        from game_templates as t
+        where guild_id = $1
+        order by name
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+    )
+    .fetch_all(&ctx.data().pool)
+    .await?;
+
+    let lines = templates
+        .into_iter()
+        .map(|template| {
+            let mut details = vec![];
+            if let Some(system) = template.system {
+                details.push(system);
+            }
+            details.push(match template.characters {
+                1 => "1 character".to_string(),
+                n => format!("{n} characters"),
+            });
+            format!("**{}** · {}", template.name, details.join(" · "))
+        })
+        .collect();
+
+    paginate()
+        .ctx(&ctx)
+        .title("Game Templates")
+        .lines(lines)
+        .empty_message("No templates yet! Save one with `/game template save`.")
+        .call()
+        .await?;
+
+    Ok(())
+}
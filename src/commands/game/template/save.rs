@@ -0,0 +1,98 @@
+use sqlx::query;
+
+use crate::{
+    commands::{contextual_args, game::can_manage},
+    Context, Error, Result,
+};
+
+/// Save this game as a template that new games can start from. Usable by game owners and moderators.
+#[poise::command(slash_command, ephemeral)]
+pub async fn save(
+    ctx: Context<'_>,
+    #[description = "Name of the template"]
+    #[min_length = 3]
+    #[max_length = 100]
+    name: String,
+    #[description = "The game to save as a template"]
+    #[autocomplete = "crate::autocomplete::game_editable"]
+    game: Option<i32>,
+    #[description = "Also save the game's characters, such as pregens"] characters: Option<bool>,
+) -> Result<()> {
+    let game = contextual_args()
+        .game_id_arg(game)
+        .ctx(&ctx)
+        .call()
+        .await?
+        .game_id;
+
+    can_manage(ctx, game).await?;
+
+    let source = query!(
+        r#"
+        select system_id, description, image, nickname_template
+        from games
+        where id = $1 and guild_id = $2
+        "#,
+        game,
+        ctx.guild_id().unwrap().get() as i64,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?
+    .ok_or(Error::NotFound)?;
+
+    let mut tx = ctx.data().pool.begin().await?;
+
+    let template = query!(
+        r#"
+        insert
+        into game_templates
+            (guild_id, name, system_id, description, image, nickname_template)
+        values
+            ($1, $2, $3, $4, $5, $6)
+        on conflict (name, guild_id) do nothing
+        returning id
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        name,
+        source.system_id,
+        source.description,
+        source.image,
+        source.nickname_template,
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| Error::Message(format!("There's already a template called `{name}`.")))?
+    .id;
+
+    let mut content = format!("Saved template `{name}`!");
+
+    if characters.unwrap_or_default() {
+        let saved = query!(
+            r#"
+            insert
+            into template_characters
+                (template_id, name, pronouns, description, image, fields)
+            select
+                $1, name, pronouns, description, image, fields
+            from characters
+            where game_id = $2
+            "#,
+            template,
+            game,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        content = format!("{content} It includes {saved} characters.");
+    }
+
+    tx.commit().await?;
+
+    ctx.say(format!(
+        "{content}\nStart a game from it with `/game create template:{name}`."
+    ))
+    .await?;
+
+    Ok(())
+}
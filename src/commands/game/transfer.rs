@@ -1,12 +1,16 @@
-use serenity::all::{Member, Mentionable};
+use poise::CreateReply;
+use serenity::all::{
+    ButtonStyle, ComponentInteraction, CreateActionRow, CreateButton, CreateInteractionResponse,
+    CreateInteractionResponseMessage, GuildId, Member, Mentionable, UserId,
+};
 use sqlx::query;
 
 use crate::{
-    commands::{confirmation_modal, contextual_args, game::can_manage},
-    Context, Result,
+    commands::{contextual_args, game::can_manage},
+    nickname, Context, Data, Error, Result,
 };
 
-/// Transfer ownership of this game to another user. Usable by game owners and server moderators.
+/// Offer ownership of this game to another user. Usable by game owners and server moderators.
 #[poise::command(slash_command)]
 pub async fn transfer(
     ctx: Context<'_>,
@@ -27,7 +31,7 @@ pub async fn transfer(
 
     let game_data = query!(
         r#"
-        select role_id, abbreviation
+        select title, owner_id
         from games
         where guild_id = $1 and id = $2
         "#,
@@ -37,73 +41,294 @@ pub async fn transfer(
     .fetch_one(&ctx.data().pool)
     .await?;
 
-    confirmation_modal()
-        .ctx(&ctx)
-        .phrase(&game_data.abbreviation)
-        .failure_message("Confirmation failed. Game was not deleted.")
-        .success_message("Game deleted!")
-        .then(|| async {
-            if also_leave.unwrap_or_default() {
-                ctx.author_member()
-                    .await
-                    .unwrap()
-                    .remove_role(ctx, game_data.role_id as u64)
-                    .await?;
-            } else {
-                query!(
-                    r#"
-                    insert
-                    into players
-                        (game_id, user_id)
-                    values
-                        ($1, (select owner_id from games where id = $1))
-                    "#,
-                    game,
-                )
-                .execute(&ctx.data().pool)
-                .await?;
-            }
+    if game_data.owner_id == user.user.id.get() as i64 {
+        return Err(Error::Message(format!(
+            "{} already owns `{}`.",
+            user.mention(),
+            game_data.title
+        )));
+    }
 
-            let record = query!(
-                r#"
-                update games
-                set
-                    owner_id = $3
-                where guild_id = $1 and id = $2
-                returning title
-                "#,
-                ctx.guild_id().unwrap().get() as i64,
-                game,
-                user.user.id.get() as i64,
-            )
-            .fetch_one(&ctx.data().pool)
-            .await?;
+    let also_leave = also_leave.unwrap_or_default();
+
+    // Making a new offer replaces any offer that is still pending for this game.
+    // The recipient has a day to accept.
+    let offer = query!(
+        r#"
+        insert
+        into transfer_offers
+            (game_id, owner_id, recipient_id, also_leave, expires_at)
+        values
+            ($1, $2, $3, $4, now() + interval '1 day')
+        on conflict (game_id)
+        do update set
+            owner_id = excluded.owner_id,
+            recipient_id = excluded.recipient_id,
+            also_leave = excluded.also_leave,
+            expires_at = excluded.expires_at
+        returning expires_at
+        "#,
+        game,
+        game_data.owner_id,
+        user.user.id.get() as i64,
+        also_leave,
+    )
+    .fetch_one(&ctx.data().pool)
+    .await?;
+
+    let owner_id = UserId::from(game_data.owner_id as u64);
+    let staying = if also_leave {
+        format!("{} will leave the game.", owner_id.mention())
+    } else {
+        format!("{} will stay on as a player.", owner_id.mention())
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "{}, you've been offered ownership of `{}`. {staying}\nThis offer expires <t:{}:R>.",
+                user.mention(),
+                game_data.title,
+                offer.expires_at.timestamp(),
+            ))
+            .components(vec![CreateActionRow::Buttons(vec![
+                CreateButton::new(format!("transfer:accept:{game}"))
+                    .label("Accept")
+                    .style(ButtonStyle::Success),
+                CreateButton::new(format!("transfer:decline:{game}"))
+                    .label("Decline")
+                    .style(ButtonStyle::Secondary),
+            ])]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Close the offer message, replacing its content.
+async fn close(
+    ctx: &serenity::all::Context,
+    press: &ComponentInteraction,
+    content: String,
+) -> Result<()> {
+    press
+        .create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Handle the recipient's answer to a transfer offer.
+pub async fn respond(
+    ctx: &serenity::all::Context,
+    data: &Data,
+    press: &ComponentInteraction,
+    action: &str,
+    game: i32,
+) -> Result<()> {
+    let maybe_offer = query!(
+        r#"
+        select
+            owner_id, recipient_id,
+            expires_at < now() as "expired!",
+            (select title from games where id = game_id) as "title!"
+        from transfer_offers
+        where game_id = $1
+        "#,
+        game,
+    )
+    .fetch_optional(&data.pool)
+    .await?;
+
+    let offer = match maybe_offer {
+        Some(offer) => offer,
+        None => return close(ctx, press, "This offer is no longer available.".to_string()).await,
+    };
 
+    let presser = press.user.id.get() as i64;
+    let recipient_id = UserId::from(offer.recipient_id as u64);
+
+    match action {
+        "decline" if presser == offer.recipient_id || presser == offer.owner_id => {
             query!(
                 r#"
                 delete
-                from players
-                where game_id = $1 and user_id = $2
+                from transfer_offers
+                where game_id = $1
                 "#,
                 game,
-                user.user.id.get() as i64,
             )
-            .execute(&ctx.data().pool)
+            .execute(&data.pool)
             .await?;
 
-            user.add_role(ctx, game_data.role_id as u64).await?;
+            let content = if presser == offer.recipient_id {
+                format!(
+                    "{} declined ownership of `{}`.",
+                    recipient_id.mention(),
+                    offer.title
+                )
+            } else {
+                format!("The offer of `{}` was withdrawn.", offer.title)
+            };
+            close(ctx, press, content).await
+        }
+        "accept" if presser == offer.recipient_id => {
+            if offer.expired {
+                query!(
+                    r#"
+                    delete
+                    from transfer_offers
+                    where game_id = $1
+                    "#,
+                    game,
+                )
+                .execute(&data.pool)
+                .await?;
 
-            ctx.say(format!(
-                "Transferred `{}` to {}",
-                record.title,
-                user.mention()
-            ))
-            .await?;
+                return close(
+                    ctx,
+                    press,
+                    format!("The offer of `{}` has expired.", offer.title),
+                )
+                .await;
+            }
 
-            Ok(())
-        })
-        .call()
+            accept(ctx, data, press, game, recipient_id).await
+        }
+        _ => Err(Error::Message("This offer isn't for you.".to_string())),
+    }
+}
+
+/// Hand the game over, all at once or not at all.
+async fn accept(
+    ctx: &serenity::all::Context,
+    data: &Data,
+    press: &ComponentInteraction,
+    game: i32,
+    recipient_id: UserId,
+) -> Result<()> {
+    let mut tx = data.pool.begin().await?;
+
+    let offer = query!(
+        r#"
+        delete
+        from transfer_offers
+        where game_id = $1 and recipient_id = $2 and expires_at > now()
+        returning owner_id, also_leave
+        "#,
+        game,
+        recipient_id.get() as i64,
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| Error::Message("This offer is no longer available.".to_string()))?;
+
+    if !offer.also_leave {
+        query!(
+            r#"
+            insert
+            into players
+                (game_id, user_id)
+            values
+                ($1, $2)
+            on conflict do nothing
+            "#,
+            game,
+            offer.owner_id,
+        )
+        .execute(&mut *tx)
         .await?;
+    }
 
-    Ok(())
+    let game_data = query!(
+        r#"
+        update games
+        set
+            owner_id = $3
+        where id = $1 and owner_id = $2
+        returning title, guild_id, role_id
+        "#,
+        game,
+        offer.owner_id,
+        recipient_id.get() as i64,
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| {
+        Error::Message("This game has changed hands since the offer was made.".to_string())
+    })?;
+
+    query!(
+        r#"
+        delete
+        from players
+        where game_id = $1 and user_id = $2
+        "#,
+        game,
+        recipient_id.get() as i64,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let guild_id = GuildId::from(game_data.guild_id as u64);
+
+    // Dropping the transaction without committing rolls everything back.
+    if ctx
+        .http
+        .add_member_role(
+            guild_id,
+            recipient_id,
+            (game_data.role_id as u64).into(),
+            Some("Accepted game transfer"),
+        )
+        .await
+        .is_err()
+    {
+        return Err(Error::Message(
+            "I couldn't give you the game's role, so nothing was transferred. Please check that I can manage roles."
+                .to_string(),
+        ));
+    }
+
+    tx.commit().await?;
+
+    let owner_id = UserId::from(offer.owner_id as u64);
+    let mut content = format!(
+        "{} accepted ownership of `{}`!",
+        recipient_id.mention(),
+        game_data.title
+    );
+
+    if offer.also_leave
+        && ctx
+            .http
+            .remove_member_role(
+                guild_id,
+                owner_id,
+                (game_data.role_id as u64).into(),
+                Some("Left game after transferring it"),
+            )
+            .await
+            .is_err()
+    {
+        content = format!(
+            "{content}\nI couldn't remove the game's role from {}.",
+            owner_id.mention()
+        );
+    }
+
+    // The new owner is no longer a player, so their character's nickname no longer applies.
+    let refreshed = nickname::refresh(ctx, &data.pool, game, recipient_id).await;
+    if let Some(note) = nickname::describe(&refreshed) {
+        content = format!("{content}\n{note}");
+    }
+
+    close(ctx, press, content).await
 }
@@ -3,7 +3,10 @@ use serenity::all::{ChannelId, RoleId, UserId};
 use sqlx::query;
 
 use crate::{
-    commands::{contextual_args, game::game_embed},
+    commands::{
+        contextual_args,
+        game::{game_embed, is_manager},
+    },
     Context, Result,
 };
 
@@ -26,7 +29,7 @@ pub async fn view(
         r#"
         select
             title, abbreviation, description, image,
-            created_at, role_id, owner_id, main_channel_id,
+            created_at, active_since, open, role_id, owner_id, main_channel_id,
             (select abbreviation from systems where id = g.system_id) as "system"
         from games as g
         where id = $1 and guild_id = $2
@@ -48,6 +51,35 @@ pub async fn view(
     .fetch_all(&ctx.data().pool)
     .await?;
 
+    let channels = query!(
+        r#"
+        select channel_id, purpose
+        from game_channels
+        where game_id = $1
+        order by purpose
+        "#,
+        game
+    )
+    .fetch_all(&ctx.data().pool)
+    .await?;
+
+    // Pending invites are only shown to the people who can revoke them.
+    let invites = if is_manager(ctx, game).await? {
+        query!(
+            r#"
+            select user_id
+            from invites
+            where game_id = $1
+            order by created_at
+            "#,
+            game
+        )
+        .fetch_all(&ctx.data().pool)
+        .await?
+    } else {
+        vec![]
+    };
+
     match maybe_game {
         Some(game) => {
             ctx.send(
@@ -59,6 +91,8 @@ pub async fn view(
                         .image(game.image)
                         .system(game.system)
                         .created_at(game.created_at)
+                        .active_since(game.active_since)
+                        .open(game.open)
                         .role_id(RoleId::from(game.role_id as u64))
                         .channel_id(game.main_channel_id.map(|c| ChannelId::from(c as u64)))
                         .owner_id(UserId::from(game.owner_id as u64))
@@ -68,6 +102,18 @@ pub async fn view(
                                 .map(|p| UserId::from(p.user_id as u64))
                                 .collect(),
                         )
+                        .channels(
+                            channels
+                                .into_iter()
+                                .map(|c| (ChannelId::from(c.channel_id as u64), c.purpose))
+                                .collect(),
+                        )
+                        .invites(
+                            invites
+                                .into_iter()
+                                .map(|i| UserId::from(i.user_id as u64))
+                                .collect(),
+                        )
                         .call(),
                 ),
             )
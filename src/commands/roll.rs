@@ -0,0 +1,174 @@
+use serenity::all::{CreateEmbed, CreateEmbedAuthor, UserId};
+use sqlx::{
+    query,
+    types::{
+        chrono::{DateTime, Utc},
+        Json,
+    },
+    PgConnection,
+};
+
+use crate::{
+    dice::{describe, Group, Roll},
+    Context, Result,
+};
+
+mod dice;
+mod stats;
+
+#[poise::command(
+    slash_command,
+    subcommand_required,
+    subcommands("dice::dice", "stats::stats"),
+    guild_only
+)]
+pub async fn roll(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Embed fields can hold at most this many characters.
+const MAX_FIELD_LENGTH: usize = 1024;
+
+/// Who can see a roll, both when it's made and in the game's log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum Visibility {
+    #[name = "Everyone"]
+    Public,
+    #[name = "Only me and the GM"]
+    Gm,
+    #[name = "Only me"]
+    Own,
+}
+
+impl Visibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Gm => "gm",
+            Visibility::Own => "self",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "public" => Some(Visibility::Public),
+            "gm" => Some(Visibility::Gm),
+            "self" => Some(Visibility::Own),
+            _ => None,
+        }
+    }
+}
+
+/// A roll from a game's log.
+#[derive(Debug)]
+pub struct LoggedRoll {
+    pub user_id: UserId,
+    /// The name of the character it was rolled as, if they still exist.
+    pub character: Option<String>,
+    pub expression: String,
+    pub total: i64,
+    pub outcome: Option<String>,
+    pub groups: Vec<Group>,
+    pub visibility: Visibility,
+    pub rolled_at: DateTime<Utc>,
+}
+
+/// The most recent rolls in a game's log that `viewer` is allowed to see, newest first.
+///
+/// Rolls made for the GM are only seen by the roller and the game's managers, and rolls made
+/// privately only by the roller.
+#[bon::builder]
+pub async fn logged_rolls(
+    conn: &mut PgConnection,
+    game: i32,
+    viewer: UserId,
+    /// Whether the viewer manages the game.
+    manager: bool,
+    /// Only rolls made as this character.
+    character: Option<i32>,
+    /// Only rolls made by this player.
+    player: Option<UserId>,
+    limit: i64,
+) -> Result<Vec<LoggedRoll>> {
+    let rolls = query!(
+        r#"
+        select
+            r.user_id, r.expression, r.total, r.outcome, r.visibility, r.rolled_at,
+            r.groups as "groups: Json<Vec<Group>>",
+            c.name as "character?"
+        from rolls as r
+        left join characters as c on c.id = r.character_id
+        where r.game_id = $1
+            and ($2::int is null or r.character_id = $2)
+            and ($3::bigint is null or r.user_id = $3)
+            and (r.visibility = 'public' or r.user_id = $4 or (r.visibility = 'gm' and $5))
+        order by r.rolled_at desc
+        limit $6
+        "#,
+        game,
+        character,
+        player.map(|p| p.get() as i64),
+        viewer.get() as i64,
+        manager,
+        limit,
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rolls
+        .into_iter()
+        .map(|r| LoggedRoll {
+            user_id: UserId::new(r.user_id as u64),
+            character: r.character,
+            expression: r.expression,
+            total: r.total,
+            outcome: r.outcome,
+            groups: r.groups.0,
+            // Anything unexpected is treated as the most hidden.
+            visibility: Visibility::parse(&r.visibility).unwrap_or(Visibility::Own),
+            rolled_at: r.rolled_at,
+        })
+        .collect())
+}
+
+#[bon::builder]
+pub fn roll_embed(
+    roller: String,
+    expression: &str,
+    /// The dice a lone rating was rolled as, if it was.
+    pool: Option<&str>,
+    #[builder(default)] references: Vec<(String, i64)>,
+    roll: &Roll,
+    outcome: Option<&str>,
+) -> CreateEmbed {
+    let title = match outcome {
+        Some(outcome) => format!("🎲 {} · {outcome}", roll.total),
+        None => format!("🎲 {}", roll.total),
+    };
+
+    let mut description = format!("`{expression}`");
+    if let Some(pool) = pool {
+        description = format!("{description} → `{pool}`");
+    }
+    for (name, value) in references {
+        description = format!("{description}\n`@{name}` is {value}");
+    }
+
+    let mut embed = CreateEmbed::new()
+        .author(CreateEmbedAuthor::new(roller))
+        .title(title)
+        .description(description);
+
+    for group in roll.groups.iter().take(25) {
+        let mut value = describe(group);
+        if value.chars().count() > MAX_FIELD_LENGTH {
+            value = format!(
+                "{}…",
+                value.chars().take(MAX_FIELD_LENGTH - 1).collect::<String>()
+            );
+        }
+        embed = embed.field(&group.label, value, true);
+    }
+
+    embed
+}
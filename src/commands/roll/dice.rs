@@ -0,0 +1,257 @@
+use poise::CreateReply;
+use serenity::all::{CreateMessage, Mentionable, UserId};
+use sqlx::{query, types::Json};
+
+use crate::{
+    commands::{
+        channel_game,
+        character::field_values,
+        contextual_args,
+        roll::{roll_embed, Visibility},
+    },
+    dice,
+    resolvers::{self, Resolver},
+    sheet, sync, Context, Error, Result,
+};
+
+/// The numbers a roll refers to, from the roller's character in this channel's game.
+async fn character_numbers(ctx: Context<'_>, references: &[String]) -> Result<Vec<(String, i64)>> {
+    let character = contextual_args()
+        .ctx(&ctx)
+        .character_id_arg(None)
+        .call()
+        .await?
+        .character_id
+        .unwrap();
+
+    let (fields, values) = field_values(&ctx.data().pool, character).await?;
+    let numbers = sheet::numbers(&fields, &values);
+
+    let mut used = vec![];
+    for name in references {
+        match numbers.iter().find(|(reference, _)| reference == name) {
+            Some(number) => {
+                if !used.contains(number) {
+                    used.push(number.clone());
+                }
+            }
+            None if numbers.is_empty() => {
+                return Err(Error::Message(format!(
+                    "`@{name}` can't be filled in, because your character doesn't have any numbers yet. Fill them in with `/character fields`."
+                )))
+            }
+            None => {
+                let available: Vec<String> =
+                    numbers.iter().map(|(reference, _)| format!("`@{reference}`")).collect();
+                return Err(Error::Message(format!(
+                    "Your character doesn't have `@{name}`. You can use {}.",
+                    available.join(", ")
+                )));
+            }
+        }
+    }
+
+    Ok(used)
+}
+
+/// Everyone other than the roller who is sent a copy of a roll for the GM: the game's owner
+/// and the members who can manage messages in the channel, as in `/game rolls`.
+async fn staff(ctx: Context<'_>, owner: UserId) -> Result<Vec<UserId>> {
+    let members = sync::guild_members(ctx.http(), ctx.guild_id().unwrap()).await?;
+    let channel = ctx.guild_channel().await;
+
+    let guild = ctx.guild().unwrap();
+    // Threads take their permissions from the channel they're in.
+    let channel = channel.as_ref().map(|channel| {
+        channel
+            .parent_id
+            .filter(|_| channel.thread_metadata.is_some())
+            .and_then(|parent| guild.channels.get(&parent))
+            .unwrap_or(channel)
+    });
+    let mut staff = vec![owner];
+    for member in members.iter().filter(|m| !m.user.bot) {
+        let permissions = match &channel {
+            Some(channel) => guild.user_permissions_in(channel, member),
+            None => guild.member_permissions(member),
+        };
+        if permissions.manage_messages() && !staff.contains(&member.user.id) {
+            staff.push(member.user.id);
+        }
+    }
+    staff.retain(|&user_id| user_id != ctx.author().id);
+
+    Ok(staff)
+}
+
+/// Roll dice, like `4d6kh3`, `d20adv + 5` or `1d20 + @str`. Usable by everyone.
+#[poise::command(slash_command)]
+pub async fn dice(
+    ctx: Context<'_>,
+    #[description = "The dice to roll, like 2d6 + 1. Use @field for your character's numbers"]
+    #[max_length = 200]
+    expression: String,
+    #[description = "Who can see the roll. Defaults to everyone"] visibility: Option<Visibility>,
+) -> Result<()> {
+    let expression = expression.trim();
+    let visibility = visibility.unwrap_or(Visibility::Public);
+    let game = channel_game(&ctx).await?;
+
+    if visibility == Visibility::Gm && game.is_none() {
+        return Err(Error::Message(
+            "Rolls for the GM can only be made in a game's channels.".to_string(),
+        ));
+    }
+    if visibility == Visibility::Gm {
+        // Finding and messaging the staff can take a while.
+        ctx.defer_ephemeral().await?;
+    }
+
+    let resolver: Option<&dyn Resolver> = match game {
+        Some(game) => query!(
+            r#"
+            select s.resolver
+            from games as g
+            join systems as s on s.id = g.system_id
+            where g.id = $1
+            "#,
+            game,
+        )
+        .fetch_optional(&ctx.data().pool)
+        .await?
+        .and_then(|s| s.resolver)
+        .and_then(|name| resolvers::find(&name)),
+        None => None,
+    };
+
+    let references = dice::references(expression);
+    let numbers = if references.is_empty() {
+        vec![]
+    } else {
+        character_numbers(ctx, &references).await?
+    };
+    let lookup = |name: &str| {
+        numbers
+            .iter()
+            .find(|(reference, _)| reference == name)
+            .map(|&(_, value)| value)
+    };
+
+    // A reference on its own is a rating, which systems that roll pools turn into dice.
+    let pool = match (references.as_slice(), resolver) {
+        ([name], Some(resolver)) if expression.eq_ignore_ascii_case(&format!("@{name}")) => {
+            lookup(name).and_then(|rating| resolver.pool(rating))
+        }
+        _ => None,
+    };
+
+    let roll = dice::parse_with(pool.as_deref().unwrap_or(expression), &lookup)
+        .and_then(|expr| expr.roll(&mut rand::thread_rng()))
+        .map_err(|e| Error::Message(e.to_string()))?;
+
+    let outcome = resolver.and_then(|resolver| resolver.resolve(&roll));
+
+    let mut roller = ctx.author_member().await.map_or_else(
+        || ctx.author().name.clone(),
+        |m| m.display_name().to_string(),
+    );
+
+    // Rolls made in a game's channels go in its log, under the roller's character if they have one.
+    if let Some(game) = game {
+        let character = query!(
+            r#"
+            select c.id, c.name
+            from players as p
+            join characters as c on c.id = p.character_id
+            where p.game_id = $1 and p.user_id = $2
+            "#,
+            game,
+            ctx.author().id.get() as i64,
+        )
+        .fetch_optional(&ctx.data().pool)
+        .await?;
+
+        query!(
+            r#"
+            insert
+            into rolls
+                (game_id, user_id, character_id, expression, total, groups, outcome, visibility)
+            values
+                ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            game,
+            ctx.author().id.get() as i64,
+            character.as_ref().map(|c| c.id),
+            expression,
+            roll.total,
+            Json(&roll.groups) as _,
+            outcome,
+            visibility.as_str(),
+        )
+        .execute(&ctx.data().pool)
+        .await?;
+
+        if let Some(character) = character {
+            roller = character.name;
+        }
+    }
+
+    let embed = roll_embed()
+        .roller(roller)
+        .expression(expression)
+        .maybe_pool(pool.as_deref())
+        .references(numbers)
+        .roll(&roll)
+        .maybe_outcome(outcome.as_deref())
+        .call();
+
+    let reply = match (visibility, game) {
+        (Visibility::Gm, Some(game)) => {
+            let game = query!(
+                r#"
+                select title, owner_id
+                from games
+                where id = $1
+                "#,
+                game,
+            )
+            .fetch_one(&ctx.data().pool)
+            .await?;
+            let owner = UserId::new(game.owner_id as u64);
+
+            let message = CreateMessage::new()
+                .content(format!(
+                    "{} rolled for the GM in **{}**:",
+                    ctx.author().mention(),
+                    game.title
+                ))
+                .embed(embed.clone());
+            let mut missed = vec![];
+            for user_id in staff(ctx, owner).await? {
+                if let Err(e) = user_id.direct_message(ctx, message.clone()).await {
+                    println!("Couldn't send a GM roll to {}: {}", user_id, e);
+                    missed.push(user_id.mention().to_string());
+                }
+            }
+
+            let mut content =
+                "Only you, the game owner and moderators can see this roll.".to_string();
+            if !missed.is_empty() {
+                content = format!(
+                    "{content} {} couldn't be sent a copy, so they'll only see it in `/game rolls`.",
+                    missed.join(", ")
+                );
+            }
+
+            CreateReply::default()
+                .content(content)
+                .embed(embed)
+                .ephemeral(true)
+        }
+        (Visibility::Own, _) => CreateReply::default().embed(embed).ephemeral(true),
+        _ => CreateReply::default().embed(embed),
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
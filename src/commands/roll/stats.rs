@@ -0,0 +1,177 @@
+use poise::CreateReply;
+use serenity::all::{CreateEmbed, Member};
+use sqlx::query;
+
+use crate::{
+    commands::{contextual_args, game::is_manager, roll::logged_rolls},
+    stats::{self, DieStats, DieType},
+    Context, Error, Result,
+};
+
+/// The most recent rolls a summary covers.
+const MAX_ROLLS: i64 = 10_000;
+
+/// The most types of dice shown, which keeps the embed within Discord's size limit.
+const MAX_DIE_TYPES: usize = 5;
+
+/// The widest a bar in the distribution can be.
+const BAR_WIDTH: u64 = 12;
+
+fn die_field(stats: &DieStats) -> (String, String) {
+    let name = format!("{} · {} rolled", stats.die.name(), stats.rolled());
+
+    let mut lines = vec![format!(
+        "Average **{:.2}**, expected {:.2}",
+        stats.average(),
+        stats.expected()
+    )];
+    if let (Some(crits), Some(fumbles)) = (stats.crits(), stats.fumbles()) {
+        lines.push(format!("Crits **{crits}** · Fumbles **{fumbles}**"));
+    }
+    lines.push(format!(
+        "Longest streaks: **{}** high, **{}** low",
+        stats.hot_streak, stats.cold_streak
+    ));
+
+    let distribution = stats.distribution();
+    let most = distribution
+        .iter()
+        .map(|&(_, count)| count)
+        .max()
+        .unwrap_or(0);
+    let labels: Vec<String> = distribution
+        .iter()
+        .map(|(faces, _)| {
+            if stats.die == DieType::Fate {
+                format!("{:+}", faces.start())
+            } else if faces.start() == faces.end() {
+                faces.start().to_string()
+            } else {
+                format!("{}-{}", faces.start(), faces.end())
+            }
+        })
+        .collect();
+    let label_width = labels.iter().map(|l| l.len()).max().unwrap_or(0);
+
+    lines.push("```".to_string());
+    for (label, (_, count)) in labels.iter().zip(&distribution) {
+        let bar = "█".repeat((count * BAR_WIDTH).div_ceil(most.max(1)) as usize);
+        lines.push(format!("{label:>label_width$} {bar} {count}"));
+    }
+    lines.push("```".to_string());
+
+    (name, lines.join("\n"))
+}
+
+/// Summarise a character's or player's rolls in a game. Usable by everyone.
+#[poise::command(slash_command, ephemeral)]
+pub async fn stats(
+    ctx: Context<'_>,
+    #[description = "The character whose rolls to summarise"]
+    #[autocomplete = "crate::autocomplete::character"]
+    character: Option<i32>,
+    #[description = "The player whose rolls to summarise. Defaults to you"] player: Option<Member>,
+    #[description = "The game to look in, if not this channel's"]
+    #[autocomplete = "crate::autocomplete::game"]
+    game: Option<i32>,
+) -> Result<()> {
+    if character.is_some() && player.is_some() {
+        return Err(Error::Message(
+            "Pick a character or a player, not both.".to_string(),
+        ));
+    }
+
+    let (game, subject, character_id, player_id) = match character {
+        Some(character) => {
+            let record = query!(
+                r#"
+                select name, game_id
+                from characters
+                where id = $1 and guild_id = $2
+                "#,
+                character,
+                ctx.guild_id().unwrap().get() as i64,
+            )
+            .fetch_optional(&ctx.data().pool)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+            (record.game_id, record.name, Some(character), None)
+        }
+        None => {
+            let game = contextual_args()
+                .game_id_arg(game)
+                .ctx(&ctx)
+                .call()
+                .await?
+                .game_id;
+            let (name, id) = match &player {
+                Some(member) => (member.display_name().to_string(), member.user.id),
+                None => (
+                    ctx.author_member().await.map_or_else(
+                        || ctx.author().name.clone(),
+                        |m| m.display_name().to_string(),
+                    ),
+                    ctx.author().id,
+                ),
+            };
+            (game, name, None, Some(id))
+        }
+    };
+
+    let title = query!(
+        r#"
+        select title
+        from games
+        where id = $1 and guild_id = $2
+        "#,
+        game,
+        ctx.guild_id().unwrap().get() as i64,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?
+    .ok_or(Error::NotFound)?
+    .title;
+
+    // Hidden rolls are only counted for those who could see them in `/game rolls`.
+    let rolls = logged_rolls()
+        .conn(&mut *ctx.data().pool.acquire().await?)
+        .game(game)
+        .viewer(ctx.author().id)
+        .manager(is_manager(ctx, game).await?)
+        .maybe_character(character_id)
+        .maybe_player(player_id)
+        .limit(MAX_ROLLS)
+        .call()
+        .await?;
+
+    if rolls.is_empty() {
+        ctx.say(format!("{subject} hasn't rolled anything in {title} yet."))
+            .await?;
+        return Ok(());
+    }
+
+    let summary = stats::summarise(rolls.iter().rev().map(|r| r.groups.as_slice()));
+
+    let mut description = format!(
+        "{} rolls, with {} dice.",
+        rolls.len(),
+        summary.iter().map(DieStats::rolled).sum::<u64>()
+    );
+    if let Some(rest) = summary.get(MAX_DIE_TYPES..).filter(|rest| !rest.is_empty()) {
+        let names: Vec<String> = rest.iter().map(|s| s.die.name()).collect();
+        description = format!("{description} Also rolled: {}.", names.join(", "));
+    }
+
+    let mut embed = CreateEmbed::new()
+        .title(format!("{subject}'s dice in {title}"))
+        .description(description);
+    for die in summary.iter().take(MAX_DIE_TYPES) {
+        let (name, value) = die_field(die);
+        embed = embed.field(name, value, false);
+    }
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
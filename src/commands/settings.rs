@@ -0,0 +1,13 @@
+use crate::{Context, Result};
+
+mod nickname;
+
+#[poise::command(
+    slash_command,
+    subcommand_required,
+    subcommands("nickname::nickname"),
+    guild_only
+)]
+pub async fn settings(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
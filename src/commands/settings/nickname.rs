@@ -0,0 +1,72 @@
+use poise::CreateReply;
+use sqlx::query;
+
+use crate::{
+    commands::confirmation_buttons,
+    nickname::{preview_embed, validate, DEFAULT_TEMPLATE},
+    Context, Error, Result,
+};
+
+/// Set the default nickname template for games in this server. Usable by server moderators.
+#[poise::command(slash_command, required_permissions = "MANAGE_MESSAGES", ephemeral)]
+pub async fn nickname(
+    ctx: Context<'_>,
+    #[description = "Template using {character} and {player}. Leave empty to reset"]
+    #[max_length = 100]
+    template: Option<String>,
+) -> Result<()> {
+    if let Some(template) = &template {
+        validate(template).map_err(|e| Error::Message(e.to_string()))?;
+    }
+
+    let preview = template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+    let samples = [
+        (
+            "Aloysius Thorne".to_string(),
+            ctx.author_member()
+                .await
+                .unwrap()
+                .display_name()
+                .to_string(),
+        ),
+        (
+            "Wolfeschlegelsteinhausenbergerdorff the Elder".to_string(),
+            "an_exceptionally_long_username".to_string(),
+        ),
+    ];
+
+    let confirmed = confirmation_buttons()
+        .ctx(&ctx)
+        .reply(
+            CreateReply::default()
+                .content("Save this as the server's default nickname template?")
+                .embed(preview_embed(preview, &samples)),
+        )
+        .confirm_label("Save")
+        .call()
+        .await?;
+
+    if !confirmed {
+        ctx.say("Nickname template was not changed.").await?;
+        return Ok(());
+    }
+
+    query!(
+        r#"
+        insert
+        into guild_settings (guild_id, nickname_template)
+        values ($1, $2)
+        on conflict (guild_id) do update
+        set nickname_template = excluded.nickname_template
+        "#,
+        ctx.guild_id().unwrap().get() as i64,
+        template,
+    )
+    .execute(&ctx.data().pool)
+    .await?;
+
+    ctx.say(format!("Default nickname template set to `{preview}`."))
+        .await?;
+
+    Ok(())
+}
@@ -3,15 +3,26 @@ use crate::{Context, Result};
 mod create;
 mod delete;
 mod edit;
+mod fields;
+mod resolver;
 mod view;
 
 use poise::Modal;
 use serenity::all::CreateEmbed;
 
+use crate::{resolvers, sheet::Field};
+
 #[poise::command(
     slash_command,
     subcommand_required,
-    subcommands("create::create", "view::view", "edit::edit", "delete::delete"),
+    subcommands(
+        "create::create",
+        "view::view",
+        "edit::edit",
+        "fields::fields",
+        "resolver::resolver",
+        "delete::delete"
+    ),
     guild_only
 )]
 pub async fn system(_: Context<'_>) -> Result<()> {
@@ -46,15 +57,33 @@ pub fn system_embed(
     abbreviation: String,
     description: RequiredStringOption,
     image: RequiredStringOption,
+    #[builder(default)] fields: Vec<Field>,
+    resolver: Option<&str>,
 ) -> CreateEmbed {
     let mut embed = CreateEmbed::new()
         .title(title)
         .field("Abbreviation", abbreviation, true);
 
+    if let Some(resolver) = resolver.and_then(resolvers::find) {
+        embed = embed.field("Rolls", resolver.description(), false);
+    }
+
     if let Some(description) = description {
         embed = embed.field("Description", description, false);
     }
 
+    if !fields.is_empty() {
+        let lines: Vec<String> = fields
+            .iter()
+            .map(|f| format!("**{}** · {}", f.name, f.kind.describe()))
+            .collect();
+        let mut value = lines.join("\n");
+        if value.chars().count() > 1024 {
+            value = format!("{}…", value.chars().take(1023).collect::<String>());
+        }
+        embed = embed.field("Character Fields", value, false);
+    }
+
     if let Some(image) = image {
         embed = embed.thumbnail(image);
     }
@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use serenity::all::{
+    CreateInputText, CreateInteractionResponse, CreateInteractionResponseMessage, CreateQuickModal,
+    InputTextStyle,
+};
+use sqlx::{query, types::Json};
+
+use crate::{
+    commands::system::system_embed,
+    sheet::{self, Field},
+    Context, Error, Result,
+};
+
+/// Define the custom fields that characters in this system's games have. Usable by server moderators.
+#[poise::command(slash_command, required_permissions = "MANAGE_MESSAGES", ephemeral)]
+pub async fn fields(
+    ctx: Context<'_>,
+    #[description = "System to define fields for"]
+    #[autocomplete = "crate::autocomplete::system"]
+    system: i32,
+) -> Result<()> {
+    let old_system = query!(
+        r#"
+        select fields as "fields: Json<Vec<Field>>"
+        from systems
+        where id = $1 and guild_id = $2
+        "#,
+        system,
+        ctx.guild_id().unwrap().get() as i64,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?
+    .ok_or(Error::NotFound)?;
+
+    let modal = CreateQuickModal::new("Character Fields")
+        .timeout(Duration::from_secs(600))
+        .field(
+            CreateInputText::new(
+                InputTextStyle::Paragraph,
+                "One field per line, as Name: type",
+                "",
+            )
+            .placeholder(
+                "Class: text\nLevel: integer\nPlaybook: choice(Cutter, Hound)\nStress: 0-9",
+            )
+            .value(sheet::describe_schema(&old_system.fields))
+            .required(false),
+        );
+
+    let Some(response) = ctx
+        .interaction
+        .quick_modal(ctx.serenity_context, modal)
+        .await?
+    else {
+        return Ok(());
+    };
+
+    let message = match sheet::parse_schema(&response.inputs[0]) {
+        Ok(fields) => {
+            let system_data = query!(
+                r#"
+                update systems
+                set fields = $3
+                where id = $1 and guild_id = $2
+                returning title, abbreviation, description, image
+                "#,
+                system,
+                ctx.guild_id().unwrap().get() as i64,
+                Json(&fields) as _,
+            )
+            .fetch_one(&ctx.data().pool)
+            .await?;
+
+            CreateInteractionResponseMessage::new()
+                .content("Fields updated! Characters in games using this system can fill them in with `/character fields`.")
+                .embed(
+                    system_embed()
+                        .title(system_data.title)
+                        .abbreviation(system_data.abbreviation)
+                        .description(system_data.description)
+                        .image(system_data.image)
+                        .fields(fields)
+                        .call(),
+                )
+        }
+        Err(problems) => CreateInteractionResponseMessage::new()
+            .content(format!("Nothing was changed.\n{}", problems.join("\n"))),
+    };
+
+    response
+        .interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(message.ephemeral(true)),
+        )
+        .await?;
+
+    Ok(())
+}
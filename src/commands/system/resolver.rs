@@ -0,0 +1,59 @@
+use poise::CreateReply;
+use sqlx::query;
+
+use crate::{commands::system::system_embed, resolvers, Context, Error, Result};
+
+/// Choose how rolls in this system's games are read. Usable by server moderators.
+#[poise::command(slash_command, required_permissions = "MANAGE_MESSAGES", ephemeral)]
+pub async fn resolver(
+    ctx: Context<'_>,
+    #[description = "System to choose a resolver for"]
+    #[autocomplete = "crate::autocomplete::system"]
+    system: i32,
+    #[description = "How to read rolls. Leave empty to only show totals"]
+    #[autocomplete = "crate::autocomplete::resolver"]
+    resolver: Option<String>,
+) -> Result<()> {
+    if let Some(name) = &resolver {
+        if resolvers::find(name).is_none() {
+            return Err(Error::Message(format!(
+                "`{name}` isn't a resolver. Pick one of {}.",
+                resolvers::RESOLVERS
+                    .iter()
+                    .map(|r| format!("`{}`", r.name()))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )));
+        }
+    }
+
+    let system_data = query!(
+        r#"
+        update systems
+        set resolver = $3
+        where id = $1 and guild_id = $2
+        returning title, abbreviation, description, image
+        "#,
+        system,
+        ctx.guild_id().unwrap().get() as i64,
+        resolver,
+    )
+    .fetch_optional(&ctx.data().pool)
+    .await?
+    .ok_or(Error::NotFound)?;
+
+    ctx.send(
+        CreateReply::default().content("Resolver updated!").embed(
+            system_embed()
+                .title(system_data.title)
+                .abbreviation(system_data.abbreviation)
+                .description(system_data.description)
+                .image(system_data.image)
+                .maybe_resolver(resolver.as_deref())
+                .call(),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use poise::CreateReply;
-use sqlx::query;
+use sqlx::{query, types::Json};
 
-use crate::{commands::system::system_embed, Context, Result};
+use crate::{commands::system::system_embed, sheet::Field, Context, Result};
 
 /// View a system's details. Usable by everyone.
 #[poise::command(slash_command)]
@@ -14,7 +14,9 @@ pub async fn view(
     let maybe_system = query!(
         r#"
         select
-            title, abbreviation, description, image
+            title, abbreviation, description, image,
+            fields as "fields: Json<Vec<Field>>",
+            resolver
         from systems
         where id = $1 and guild_id = $2
         "#,
@@ -33,6 +35,8 @@ pub async fn view(
                         .abbreviation(system.abbreviation)
                         .description(system.description)
                         .image(system.image)
+                        .fields(system.fields.0)
+                        .maybe_resolver(system.resolver.as_deref())
                         .call(),
                 ),
             )
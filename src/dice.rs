@@ -0,0 +1,670 @@
+//! Dice expressions, like `4d6kh3 + 2`.
+//!
+//! An expression is numbers and dice joined with `+`, `-`, `*` and `/`, grouped with parentheses.
+//! Dice are written `NdM`, where `N` defaults to 1, `d%` is a d100 and `dF` is a Fate die
+//! with the faces -1, 0 and +1, followed by any of:
+//!
+//! - `adv` or `dis`: roll a single die twice and keep the higher or lower, like `d20adv`
+//! - `khN`, `klN`, `dhN` or `dlN`: keep or drop the `N` highest or lowest dice
+//! - `!` or `!C`: roll another die whenever one comes up on its highest face, or matches `C`
+//! - `rC` or `roC`: reroll dice that match `C`, until they don't or only once
+//! - `C` with a `>`, `<` or `=`: count the dice that match `C` as successes, instead of adding them up
+//!
+//! `C` is a comparison against a face: `5` or `=5` is exactly 5, `>5` is 5 or more, and `<5` is 5 or less.
+//!
+//! Numbers can also be written as references, like `@str`, which are filled in by whoever parses
+//! the expression. See [`parse_with`].
+
+use std::iter::Peekable;
+use std::ops::RangeInclusive;
+use std::str::CharIndices;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// The most dice a single group can roll, before explosions and rerolls.
+pub const MAX_DICE: u32 = 100;
+
+/// The most sides a die can have.
+pub const MAX_SIDES: u32 = 1000;
+
+/// The most extra dice explosions and rerolls can add to a single group.
+const MAX_EXTRA: usize = 100;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum DiceError {
+    #[error("I expected {expected} at position {position}.")]
+    Expected {
+        expected: &'static str,
+        position: usize,
+    },
+    #[error("Roll between 1 and {MAX_DICE} dice at a time.")]
+    DiceCount,
+    #[error("Dice can have between 1 and {MAX_SIDES} sides.")]
+    Sides,
+    #[error("`{0}` would never stop rolling.")]
+    Forever(String),
+    #[error("`{0}` can only be used once for each group of dice.")]
+    Repeated(&'static str),
+    #[error("Advantage and disadvantage only work on a single die, like `d20adv`.")]
+    Advantage,
+    #[error(
+        "Advantage and disadvantage already keep one die, so they can't be used with keep or drop."
+    )]
+    AdvantageKeep,
+    #[error("You can't divide by zero.")]
+    DivideByZero,
+    #[error("That number is too big.")]
+    TooBig,
+    #[error("I don't know what `@{0}` is.")]
+    UnknownReference(String),
+}
+
+type Result<T> = std::result::Result<T, DiceError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compare {
+    Equal(i64),
+    AtLeast(i64),
+    AtMost(i64),
+}
+
+impl Compare {
+    pub fn matches(self, value: i64) -> bool {
+        match self {
+            Compare::Equal(target) => value == target,
+            Compare::AtLeast(target) => value >= target,
+            Compare::AtMost(target) => value <= target,
+        }
+    }
+
+    /// Whether every face of a die matches, so rerolling or exploding on it would never end.
+    fn matches_every_face(self, faces: RangeInclusive<i64>) -> bool {
+        faces.into_iter().all(|face| self.matches(face))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    Highest(u32),
+    Lowest(u32),
+    DropHighest(u32),
+    DropLowest(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Advantage {
+    Advantage,
+    Disadvantage,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dice {
+    /// The dice as they were written.
+    pub label: String,
+    pub count: u32,
+    pub sides: u32,
+    /// Whether these are Fate dice, rather than numbered from 1.
+    pub fate: bool,
+    pub advantage: Option<Advantage>,
+    pub keep: Option<Keep>,
+    pub explode: Option<Compare>,
+    /// What to reroll, and whether to only reroll once.
+    pub reroll: Option<(Compare, bool)>,
+    pub successes: Option<Compare>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Number(i64),
+    Dice(Dice),
+    Negate(Box<Expr>),
+    Binary(Box<Expr>, Operator, Box<Expr>),
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    lookup: &'a dyn Fn(&str) -> Option<i64>,
+}
+
+impl<'a> Parser<'a> {
+    fn position(&mut self) -> usize {
+        self.chars.peek().map_or(self.input.len(), |&(i, _)| i)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.chars.next();
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let position = self.position();
+        if self.input[position..].starts_with(word) {
+            for _ in word.chars() {
+                self.chars.next();
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expected(&mut self, expected: &'static str) -> DiceError {
+        let position = self.position();
+        DiceError::Expected {
+            expected,
+            position: self.input[..position].chars().count() + 1,
+        }
+    }
+
+    fn number(&mut self) -> Option<Result<i64>> {
+        let start = self.position();
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.chars.next();
+        }
+        let end = self.position();
+        (start != end).then(|| {
+            self.input[start..end]
+                .parse()
+                .map_err(|_| DiceError::TooBig)
+        })
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut left = self.term()?;
+        loop {
+            self.skip_whitespace();
+            let operator = if self.eat('+') {
+                Operator::Add
+            } else if self.eat('-') {
+                Operator::Subtract
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(Box::new(left), operator, Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr> {
+        let mut left = self.unary()?;
+        loop {
+            self.skip_whitespace();
+            let operator = if self.eat('*') {
+                Operator::Multiply
+            } else if self.eat('/') {
+                Operator::Divide
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(Box::new(left), operator, Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        self.skip_whitespace();
+        if self.eat('-') {
+            Ok(Expr::Negate(Box::new(self.unary()?)))
+        } else if self.eat('+') {
+            self.unary()
+        } else {
+            self.atom()
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr> {
+        self.skip_whitespace();
+
+        if self.eat('(') {
+            let expr = self.expr()?;
+            self.skip_whitespace();
+            if !self.eat(')') {
+                return Err(self.expected("a `)`"));
+            }
+            return Ok(expr);
+        }
+
+        if self.eat('@') {
+            let start = self.position();
+            while self.peek().is_some_and(is_reference_char) {
+                self.chars.next();
+            }
+            let name = &self.input[start..self.position()];
+            if name.is_empty() {
+                return Err(self.expected("a name after `@`"));
+            }
+            return (self.lookup)(name)
+                .map(Expr::Number)
+                .ok_or_else(|| DiceError::UnknownReference(name.to_string()));
+        }
+
+        let start = self.position();
+        let count = self.number().transpose()?;
+
+        if self.peek() != Some('d') {
+            return match count {
+                Some(count) => Ok(Expr::Number(count)),
+                None => Err(self.expected("a number or dice")),
+            };
+        }
+        self.chars.next();
+
+        let count = match count {
+            None => 1,
+            Some(count) if (1..=MAX_DICE as i64).contains(&count) => count as u32,
+            Some(_) => return Err(DiceError::DiceCount),
+        };
+
+        let fate = self.eat('f');
+        let sides = if fate {
+            3
+        } else if self.eat('%') {
+            100
+        } else {
+            match self.number().transpose()? {
+                Some(sides) if (1..=MAX_SIDES as i64).contains(&sides) => sides as u32,
+                Some(_) => return Err(DiceError::Sides),
+                None => return Err(self.expected("a number of sides")),
+            }
+        };
+
+        let mut dice = Dice {
+            label: String::new(),
+            count,
+            sides,
+            fate,
+            advantage: None,
+            keep: None,
+            explode: None,
+            reroll: None,
+            successes: None,
+        };
+        let mut end = self.position();
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('a') if self.eat_word("adv") => {
+                    set(&mut dice.advantage, Advantage::Advantage, "adv")?;
+                }
+                Some('d') if self.eat_word("dis") => {
+                    set(&mut dice.advantage, Advantage::Disadvantage, "dis")?;
+                }
+                Some('k') => {
+                    self.chars.next();
+                    let lowest = self.eat('l');
+                    if !lowest {
+                        self.eat('h');
+                    }
+                    let n = self.count()?;
+                    let keep = if lowest {
+                        Keep::Lowest(n)
+                    } else {
+                        Keep::Highest(n)
+                    };
+                    set(&mut dice.keep, keep, "keep and drop")?;
+                }
+                Some('d') => {
+                    self.chars.next();
+                    let keep = if self.eat('h') {
+                        Keep::DropHighest(self.count()?)
+                    } else if self.eat('l') {
+                        Keep::DropLowest(self.count()?)
+                    } else {
+                        return Err(self.expected("`h` or `l` after `d`"));
+                    };
+                    set(&mut dice.keep, keep, "keep and drop")?;
+                }
+                Some('!') => {
+                    self.chars.next();
+                    let on = match self.compare(true)? {
+                        Some(on) => on,
+                        None => Compare::Equal(*dice.faces().end()),
+                    };
+                    set(&mut dice.explode, on, "!")?;
+                }
+                Some('r') => {
+                    self.chars.next();
+                    let once = self.eat('o');
+                    let on = match self.compare(true)? {
+                        Some(on) => on,
+                        None => return Err(self.expected("a face to reroll")),
+                    };
+                    set(&mut dice.reroll, (on, once), "r")?;
+                }
+                Some('>' | '<' | '=') => {
+                    let target = self.compare(false)?.unwrap();
+                    set(&mut dice.successes, target, "a target")?;
+                }
+                _ => break,
+            }
+            end = self.position();
+        }
+
+        dice.label = self.input[start..end].to_string();
+
+        if dice.advantage.is_some() && dice.count != 1 {
+            return Err(DiceError::Advantage);
+        }
+        if dice.advantage.is_some() && dice.keep.is_some() {
+            return Err(DiceError::AdvantageKeep);
+        }
+        if dice
+            .explode
+            .is_some_and(|on| on.matches_every_face(dice.faces()))
+        {
+            return Err(DiceError::Forever(dice.label));
+        }
+        if dice
+            .reroll
+            .is_some_and(|(on, once)| !once && on.matches_every_face(dice.faces()))
+        {
+            return Err(DiceError::Forever(dice.label));
+        }
+
+        Ok(Expr::Dice(dice))
+    }
+
+    /// A number of dice to keep or drop, which defaults to 1.
+    fn count(&mut self) -> Result<u32> {
+        match self.number().transpose()? {
+            None => Ok(1),
+            Some(n) => u32::try_from(n).map_err(|_| DiceError::TooBig),
+        }
+    }
+
+    /// A comparison against a face. Without `bare`, it has to start with `>`, `<` or `=`.
+    fn compare(&mut self, bare: bool) -> Result<Option<Compare>> {
+        let make: Option<fn(i64) -> Compare> = if self.eat('>') {
+            Some(Compare::AtLeast)
+        } else if self.eat('<') {
+            Some(Compare::AtMost)
+        } else if self.eat('=') {
+            Some(Compare::Equal)
+        } else {
+            None
+        };
+        if make.is_none() && !bare {
+            return Ok(None);
+        }
+        match (make, self.number().transpose()?) {
+            (Some(make), Some(n)) => Ok(Some(make(n))),
+            (Some(_), None) => Err(self.expected("a number to compare with")),
+            (None, n) => Ok(n.map(Compare::Equal)),
+        }
+    }
+}
+
+fn set<T>(slot: &mut Option<T>, value: T, name: &'static str) -> Result<()> {
+    if slot.is_some() {
+        return Err(DiceError::Repeated(name));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Whether a character can be part of a reference's name, after the `@`.
+fn is_reference_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The names of the references in an expression, like `str` for `1d20 + @str`, lowercased.
+pub fn references(input: &str) -> Vec<String> {
+    let input = input.to_lowercase();
+    input
+        .split('@')
+        .skip(1)
+        .map(|rest| rest.chars().take_while(|&c| is_reference_char(c)).collect())
+        .filter(|name: &String| !name.is_empty())
+        .collect()
+}
+
+/// Read a dice expression without any references.
+pub fn parse(input: &str) -> Result<Expr> {
+    parse_with(input, &|_| None)
+}
+
+/// Read a dice expression, filling in references with `lookup`, which is given their lowercased names.
+pub fn parse_with(input: &str, lookup: &dyn Fn(&str) -> Option<i64>) -> Result<Expr> {
+    let input = input.trim().to_lowercase();
+    let mut parser = Parser {
+        input: &input,
+        chars: input.char_indices().peekable(),
+        lookup,
+    };
+    let expr = parser.expr()?;
+    parser.skip_whitespace();
+    if parser.peek().is_some() {
+        return Err(parser.expected("`+`, `-`, `*` or `/`"));
+    }
+    Ok(expr)
+}
+
+/// One die that was rolled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Die {
+    pub value: i64,
+    /// Whether the die counts towards the total. Dropped and rerolled dice don't.
+    pub kept: bool,
+    /// Whether this die caused another to be rolled.
+    #[serde(default)]
+    pub exploded: bool,
+    /// Whether this die was rolled again.
+    #[serde(default)]
+    pub rerolled: bool,
+}
+
+/// The dice rolled for one `NdM` in an expression.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Group {
+    pub label: String,
+    pub sides: u32,
+    #[serde(default)]
+    pub fate: bool,
+    pub dice: Vec<Die>,
+    /// Whether the total counts successes rather than adding up faces.
+    #[serde(default)]
+    pub successes: bool,
+    pub total: i64,
+}
+
+/// The result of rolling an expression.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Roll {
+    pub total: i64,
+    pub groups: Vec<Group>,
+}
+
+impl Dice {
+    /// The values the dice can land on.
+    pub fn faces(&self) -> RangeInclusive<i64> {
+        if self.fate {
+            -1..=1
+        } else {
+            1..=self.sides as i64
+        }
+    }
+
+    fn roll(&self, rng: &mut impl Rng) -> Group {
+        let (count, keep) = match self.advantage {
+            Some(Advantage::Advantage) => (2, Some(Keep::Highest(1))),
+            Some(Advantage::Disadvantage) => (2, Some(Keep::Lowest(1))),
+            None => (self.count, self.keep),
+        };
+
+        let faces = self.faces();
+        let mut face = || rng.gen_range(faces.clone());
+        let mut dice = vec![];
+        let mut extra = 0;
+
+        for _ in 0..count {
+            let mut value = face();
+
+            if let Some((on, once)) = self.reroll {
+                while on.matches(value) && extra < MAX_EXTRA {
+                    dice.push(Die {
+                        value,
+                        kept: false,
+                        exploded: false,
+                        rerolled: true,
+                    });
+                    value = face();
+                    extra += 1;
+                    if once {
+                        break;
+                    }
+                }
+            }
+
+            dice.push(Die {
+                value,
+                kept: true,
+                exploded: false,
+                rerolled: false,
+            });
+
+            if let Some(on) = self.explode {
+                while on.matches(value) && extra < MAX_EXTRA {
+                    dice.last_mut().unwrap().exploded = true;
+                    value = face();
+                    dice.push(Die {
+                        value,
+                        kept: true,
+                        exploded: false,
+                        rerolled: false,
+                    });
+                    extra += 1;
+                }
+            }
+        }
+
+        if let Some(keep) = keep {
+            let mut rolled: Vec<usize> = (0..dice.len()).filter(|&i| dice[i].kept).collect();
+            // Highest first. The sort is stable, so ties stay in the order they were rolled.
+            rolled.sort_by_key(|&i| std::cmp::Reverse(dice[i].value));
+            let n = rolled.len();
+            let dropped = match keep {
+                Keep::Highest(k) => rolled[(k as usize).min(n)..].to_vec(),
+                Keep::Lowest(k) => rolled[..n - (k as usize).min(n)].to_vec(),
+                Keep::DropHighest(k) => rolled[..(k as usize).min(n)].to_vec(),
+                Keep::DropLowest(k) => rolled[n - (k as usize).min(n)..].to_vec(),
+            };
+            for i in dropped {
+                dice[i].kept = false;
+            }
+        }
+
+        let kept = dice.iter().filter(|d| d.kept);
+        let total = match self.successes {
+            Some(target) => kept.filter(|d| target.matches(d.value)).count() as i64,
+            None => kept.map(|d| d.value).sum(),
+        };
+
+        Group {
+            label: self.label.clone(),
+            sides: self.sides,
+            fate: self.fate,
+            dice,
+            successes: self.successes.is_some(),
+            total,
+        }
+    }
+}
+
+impl Expr {
+    /// Roll every die in the expression and work out the total.
+    pub fn roll(&self, rng: &mut impl Rng) -> Result<Roll> {
+        let mut groups = vec![];
+        let total = self.evaluate(rng, &mut groups)?;
+        Ok(Roll { total, groups })
+    }
+
+    fn evaluate(&self, rng: &mut impl Rng, groups: &mut Vec<Group>) -> Result<i64> {
+        match self {
+            Expr::Number(n) => Ok(*n),
+            Expr::Dice(dice) => {
+                let group = dice.roll(rng);
+                let total = group.total;
+                groups.push(group);
+                Ok(total)
+            }
+            Expr::Negate(expr) => expr
+                .evaluate(rng, groups)?
+                .checked_neg()
+                .ok_or(DiceError::TooBig),
+            Expr::Binary(left, operator, right) => {
+                let left = left.evaluate(rng, groups)?;
+                let right = right.evaluate(rng, groups)?;
+                match operator {
+                    Operator::Add => left.checked_add(right),
+                    Operator::Subtract => left.checked_sub(right),
+                    Operator::Multiply => left.checked_mul(right),
+                    Operator::Divide if right == 0 => return Err(DiceError::DivideByZero),
+                    Operator::Divide => left.checked_div_euclid(right),
+                }
+                .ok_or(DiceError::TooBig)
+            }
+        }
+    }
+}
+
+/// Parse and roll an expression in one go.
+pub fn roll(input: &str, rng: &mut impl Rng) -> Result<Roll> {
+    parse(input)?.roll(rng)
+}
+
+/// Show the dice in a group, with dropped and rerolled dice struck out and exploding dice marked.
+pub fn describe(group: &Group) -> String {
+    let dice: Vec<String> = group
+        .dice
+        .iter()
+        .map(|die| {
+            let mut value = match die.value {
+                1 if group.fate => "+".to_string(),
+                -1 if group.fate => "-".to_string(),
+                value => value.to_string(),
+            };
+            if die.exploded {
+                value.push('!');
+            }
+            if die.kept {
+                value
+            } else {
+                format!("~~{value}~~")
+            }
+        })
+        .collect();
+
+    let total = if group.successes {
+        match group.total {
+            1 => "1 success".to_string(),
+            n => format!("{n} successes"),
+        }
+    } else {
+        group.total.to_string()
+    };
+
+    format!("[{}] = {total}", dice.join(", "))
+}
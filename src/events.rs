@@ -0,0 +1,249 @@
+use poise::FrameworkContext;
+use serenity::all::{
+    ChannelId, ComponentInteraction, Context, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, EditRole, FullEvent, GuildId, Interaction,
+    Mentionable, RoleId, UserId,
+};
+use sqlx::query;
+
+use crate::{commands, nickname, Data, Error, Result};
+
+pub async fn handle(
+    ctx: &Context,
+    event: &FullEvent,
+    _framework: FrameworkContext<'_, Data, Error>,
+    data: &Data,
+) -> Result<()> {
+    match event {
+        FullEvent::GuildMemberRemoval { guild_id, user, .. } => {
+            member_removed(ctx, data, *guild_id, user.id).await
+        }
+        FullEvent::GuildRoleDelete {
+            guild_id,
+            removed_role_id,
+            ..
+        } => role_deleted(ctx, data, *guild_id, *removed_role_id).await,
+        FullEvent::ChannelDelete { channel, .. } => {
+            channel_deleted(data, channel.guild_id, channel.id).await
+        }
+        FullEvent::InteractionCreate {
+            interaction: Interaction::Component(press),
+        } => component_pressed(ctx, data, press).await,
+        _ => Ok(()),
+    }
+}
+
+/// Send a user a direct message, logging instead of failing if their DMs are closed.
+pub async fn notify(ctx: &Context, user_id: UserId, content: String) {
+    if let Err(e) = user_id
+        .direct_message(ctx, CreateMessage::new().content(content))
+        .await
+    {
+        println!("Couldn't notify {}: {}", user_id, e);
+    }
+}
+
+/// Answer a button press, showing the presser any message meant for them.
+async fn component_pressed(ctx: &Context, data: &Data, press: &ComponentInteraction) -> Result<()> {
+    match commands::component(ctx, data, press).await {
+        Err(Error::Message(message)) => {
+            press
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content(message)
+                            .ephemeral(true),
+                    ),
+                )
+                .await?;
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// Remove a departed member from every game in the server, releasing their characters.
+async fn member_removed(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<()> {
+    let removed = query!(
+        r#"
+        delete
+        from players as p
+        using games as g
+        where
+            p.game_id = g.id
+            and
+            g.guild_id = $1
+            and
+            p.user_id = $2
+        returning
+            g.title, g.owner_id,
+            (select name from characters where id = p.character_id) as "character"
+        "#,
+        guild_id.get() as i64,
+        user_id.get() as i64,
+    )
+    .fetch_all(&data.pool)
+    .await?;
+
+    nickname::forget(&data.pool, guild_id, user_id).await?;
+
+    query!(
+        r#"
+        delete
+        from invites
+        where guild_id = $1 and user_id = $2
+        "#,
+        guild_id.get() as i64,
+        user_id.get() as i64,
+    )
+    .execute(&data.pool)
+    .await?;
+
+    for game in removed {
+        let released = match game.character {
+            Some(character) => format!(" `{character}` was released."),
+            None => String::new(),
+        };
+        notify(
+            ctx,
+            UserId::from(game.owner_id as u64),
+            format!(
+                "{} left the server, so they were removed from `{}`.{released}",
+                user_id.mention(),
+                game.title,
+            ),
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
+/// Recreate a game's role when it is deleted, and hand it back out to everyone in the game.
+async fn role_deleted(
+    ctx: &Context,
+    data: &Data,
+    guild_id: GuildId,
+    role_id: RoleId,
+) -> Result<()> {
+    let maybe_game = query!(
+        r#"
+        select
+            id, title, abbreviation, owner_id,
+            array(select user_id from players where game_id = g.id) as "players!"
+        from games as g
+        where guild_id = $1 and role_id = $2
+        "#,
+        guild_id.get() as i64,
+        role_id.get() as i64,
+    )
+    .fetch_optional(&data.pool)
+    .await?;
+
+    let game = match maybe_game {
+        Some(game) => game,
+        None => return Ok(()),
+    };
+
+    let owner_id = UserId::from(game.owner_id as u64);
+
+    let role = match guild_id
+        .create_role(
+            ctx,
+            EditRole::new()
+                .name(game.abbreviation)
+                .audit_log_reason("Game role recreated after deletion")
+                .mentionable(true),
+        )
+        .await
+    {
+        Ok(role) => role,
+        Err(e) => {
+            notify(
+                ctx,
+                owner_id,
+                format!(
+                    "The role for `{}` was deleted and I couldn't recreate it. Try `/game sync` once the role is back.",
+                    game.title,
+                ),
+            )
+            .await;
+            return Err(e.into());
+        }
+    };
+
+    query!(
+        r#"
+        update games
+        set role_id = $2
+        where id = $1
+        "#,
+        game.id,
+        role.id.get() as i64,
+    )
+    .execute(&data.pool)
+    .await?;
+
+    for user_id in std::iter::once(game.owner_id).chain(game.players) {
+        if let Err(e) = ctx
+            .http
+            .add_member_role(
+                guild_id,
+                UserId::from(user_id as u64),
+                role.id,
+                Some("Game role recreated after deletion"),
+            )
+            .await
+        {
+            println!("Couldn't restore role for {}: {}", user_id, e);
+        }
+    }
+
+    notify(
+        ctx,
+        owner_id,
+        format!(
+            "The role for `{}` was deleted, so I recreated it as {}.",
+            game.title,
+            role.mention(),
+        ),
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Forget a deleted channel so it no longer counts as a game channel.
+async fn channel_deleted(data: &Data, guild_id: GuildId, channel_id: ChannelId) -> Result<()> {
+    query!(
+        r#"
+        update games
+        set main_channel_id = null
+        where guild_id = $1 and main_channel_id = $2
+        "#,
+        guild_id.get() as i64,
+        channel_id.get() as i64,
+    )
+    .execute(&data.pool)
+    .await?;
+
+    query!(
+        r#"
+        delete
+        from game_channels
+        where guild_id = $1 and channel_id = $2
+        "#,
+        guild_id.get() as i64,
+        channel_id.get() as i64,
+    )
+    .execute(&data.pool)
+    .await?;
+
+    Ok(())
+}
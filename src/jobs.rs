@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use serenity::all::Context;
+
+use crate::{announcements, sync, DB};
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const ANNOUNCEMENT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Start the bot's periodic background jobs.
+pub fn spawn(ctx: Context, pool: DB) {
+    tokio::spawn(sync_roles(ctx.clone(), pool.clone()));
+    tokio::spawn(post_announcements(ctx, pool));
+}
+
+async fn sync_roles(ctx: Context, pool: DB) {
+    let mut interval = tokio::time::interval(SYNC_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = sync::sync_all(&ctx.http, &pool).await {
+            println!("Error in role sync job: {}", e);
+        }
+    }
+}
+
+async fn post_announcements(ctx: Context, pool: DB) {
+    let mut interval = tokio::time::interval(ANNOUNCEMENT_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = announcements::post_due(&ctx, &pool).await {
+            println!("Error in announcement job: {}", e);
+        }
+    }
+}
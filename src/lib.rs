@@ -1,9 +1,20 @@
 use sqlx::{Pool, Postgres};
 
+pub mod announcements;
 pub mod autocomplete;
+pub mod bundle;
 pub mod commands;
+pub mod dice;
+pub mod jobs;
+pub mod nickname;
+pub mod resolvers;
+pub mod sheet;
+pub mod spreadsheet;
+pub mod stats;
+pub mod sync;
 
 pub mod error;
+pub mod events;
 
 pub use error::{Error, Result};
 
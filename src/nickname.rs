@@ -0,0 +1,566 @@
+use futures::{stream, StreamExt};
+use serenity::all::{
+    CacheHttp, CreateEmbed, EditMember, GuildId, HttpError, Member, Mentionable, UserId,
+};
+use sqlx::{query, query_as, PgConnection};
+
+use crate::{Error, Result, DB};
+
+/// How many members' nicknames are changed at once. Discord's rate limits are handled by
+/// serenity, this just keeps a large game from queueing every request up front.
+const CONCURRENCY: usize = 5;
+
+/// Used when neither the game nor the server has set a nickname template.
+pub const DEFAULT_TEMPLATE: &str = "{player} ({character})";
+
+/// Discord's limit on the length of a nickname.
+pub const MAX_LENGTH: usize = 32;
+
+const CHARACTER: &str = "{character}";
+const PLAYER: &str = "{player}";
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum TemplateError {
+    #[error("Templates must include `{{character}}`.")]
+    MissingCharacter,
+    #[error("`{{{0}}}` isn't something I can fill in. Use `{{character}}` or `{{player}}`.")]
+    UnknownPlaceholder(String),
+    #[error("There's a `{{` without a matching `}}`.")]
+    Unclosed,
+    #[error("Templates can't be more than {MAX_LENGTH} characters without the names filled in.")]
+    TooLong,
+}
+
+/// Check that a template only uses known placeholders and includes the character's name.
+pub fn validate(template: &str) -> std::result::Result<(), TemplateError> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => return Err(TemplateError::Unclosed),
+        };
+        let placeholder = &rest[start..=end];
+        if placeholder != CHARACTER && placeholder != PLAYER {
+            return Err(TemplateError::UnknownPlaceholder(
+                rest[start + 1..end].to_string(),
+            ));
+        }
+        rest = &rest[end + 1..];
+    }
+
+    if !template.contains(CHARACTER) {
+        return Err(TemplateError::MissingCharacter);
+    }
+
+    if fill(template, "", "").chars().count() > MAX_LENGTH {
+        return Err(TemplateError::TooLong);
+    }
+
+    Ok(())
+}
+
+/// Fill in both placeholders in one pass, so a name that happens to contain a placeholder
+/// is left as it is.
+fn fill(template: &str, character: &str, player: &str) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix(CHARACTER) {
+            filled.push_str(character);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix(PLAYER) {
+            filled.push_str(player);
+            rest = after;
+        } else {
+            filled.push('{');
+            rest = &rest[1..];
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+fn first_name(name: &str) -> &str {
+    name.split_whitespace().next().unwrap_or(name)
+}
+
+fn truncate(name: &str, length: usize) -> String {
+    if name.chars().count() <= length {
+        name.to_string()
+    } else if length <= 1 {
+        name.chars().take(length).collect()
+    } else {
+        let mut truncated: String = name.chars().take(length - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// Fill in a template, abbreviating names until the result fits in a nickname.
+///
+/// Names are shortened to their first word, then the player's name is truncated,
+/// and finally the character's name.
+pub fn render(template: &str, character: &str, player: &str) -> String {
+    let full = fill(template, character, player);
+    if full.chars().count() <= MAX_LENGTH {
+        return full;
+    }
+
+    let character = first_name(character);
+    let shortened = fill(template, character, player);
+    if shortened.chars().count() <= MAX_LENGTH {
+        return shortened;
+    }
+
+    let player = first_name(player);
+    let shortened = fill(template, character, player);
+    if shortened.chars().count() <= MAX_LENGTH {
+        return shortened;
+    }
+
+    let fixed = fill(template, "", "").chars().count();
+    let character_count = template.matches(CHARACTER).count();
+    let player_count = template.matches(PLAYER).count();
+    let character_length = character.chars().count();
+    let budget = MAX_LENGTH.saturating_sub(fixed);
+
+    // Give the character's name as much room as it needs, and the player's gets what's left.
+    let player_length = budget
+        .saturating_sub(character_length * character_count)
+        .checked_div(player_count)
+        .unwrap_or(0);
+    let player = truncate(player, player_length.max(1));
+    let player_length = player.chars().count();
+
+    let character_length = budget
+        .saturating_sub(player_length * player_count)
+        .checked_div(character_count)
+        .unwrap_or(0);
+    let character = truncate(character, character_length);
+
+    fill(template, &character, &player)
+        .chars()
+        .take(MAX_LENGTH)
+        .collect()
+}
+
+/// Find the template a game uses, falling back to the server's default and then the built-in one.
+pub async fn template(pool: &DB, game: i32) -> Result<String> {
+    let record = query!(
+        r#"
+        select
+            coalesce(
+                nickname_template,
+                (select nickname_template from guild_settings where guild_id = g.guild_id)
+            ) as "template"
+        from games as g
+        where id = $1
+        "#,
+        game,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(record
+        .template
+        .unwrap_or_else(|| DEFAULT_TEMPLATE.to_string()))
+}
+
+/// Show how a template fills in for some characters and players.
+pub fn preview_embed(template: &str, samples: &[(String, String)]) -> CreateEmbed {
+    let lines = samples
+        .iter()
+        .map(|(character, player)| format!("{player} → {}", render(template, character, player)))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    CreateEmbed::new()
+        .title("Nickname Preview")
+        .field("Template", format!("`{template}`"), false)
+        .field("Examples", format!("```\n{lines}\n```"), false)
+}
+
+/// A member's nickname before and after it was changed.
+#[derive(Debug)]
+pub struct Rename {
+    pub from: String,
+    pub to: String,
+}
+
+/// The name a member goes by without any game's changes.
+pub fn base_name(original: Option<&str>, member: &Member) -> String {
+    original
+        .or(member.user.global_name.as_deref())
+        .unwrap_or(&member.user.name)
+        .to_string()
+}
+
+/// Remember a member's nickname from before any game renamed them.
+/// An existing snapshot is kept, since it hasn't been restored yet.
+/// Returns the remembered nickname.
+pub async fn snapshot(pool: &DB, guild_id: GuildId, member: &Member) -> Result<Option<String>> {
+    let record = query!(
+        r#"
+        with inserted as (
+            insert
+            into nickname_snapshots (guild_id, user_id, nickname)
+            values ($1, $2, $3)
+            on conflict do nothing
+            returning nickname
+        )
+        select nickname from inserted
+        union all
+        select nickname from nickname_snapshots where guild_id = $1 and user_id = $2
+        limit 1
+        "#,
+        guild_id.get() as i64,
+        member.user.id.get() as i64,
+        member.nick,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(record.nickname)
+}
+
+/// Rename a member after their character, remembering their original nickname first.
+/// Returns `None` if they already had that nickname.
+pub async fn apply(
+    http: impl CacheHttp,
+    pool: &DB,
+    guild_id: GuildId,
+    user_id: UserId,
+    template: &str,
+    character: &str,
+) -> Result<Option<Rename>> {
+    let member = guild_id.member(&http, user_id).await?;
+    let original = snapshot(pool, guild_id, &member).await?;
+    let nickname = render(
+        template,
+        character,
+        &base_name(original.as_deref(), &member),
+    );
+
+    if member.nick.as_deref() == Some(nickname.as_str()) {
+        return Ok(None);
+    }
+
+    guild_id
+        .edit_member(
+            &http,
+            user_id,
+            EditMember::new()
+                .nickname(&nickname)
+                .audit_log_reason("Character nickname applied"),
+        )
+        .await?;
+
+    Ok(Some(Rename {
+        from: member.display_name().to_string(),
+        to: nickname,
+    }))
+}
+
+/// Put back a member's nickname from before any game renamed them.
+/// The snapshot is only forgotten once the nickname has been restored.
+/// Returns `None` if there was nothing to restore.
+pub async fn restore(
+    http: impl CacheHttp,
+    pool: &DB,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Option<Rename>> {
+    let maybe_snapshot = query!(
+        r#"
+        select nickname
+        from nickname_snapshots
+        where guild_id = $1 and user_id = $2
+        "#,
+        guild_id.get() as i64,
+        user_id.get() as i64,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let original = match maybe_snapshot {
+        Some(snapshot) => snapshot.nickname,
+        None => return Ok(None),
+    };
+
+    let member = guild_id.member(&http, user_id).await?;
+
+    guild_id
+        .edit_member(
+            &http,
+            user_id,
+            EditMember::new()
+                .nickname(original.clone().unwrap_or_default())
+                .audit_log_reason("Original nickname restored"),
+        )
+        .await?;
+
+    forget(pool, guild_id, user_id).await?;
+
+    Ok(Some(Rename {
+        from: member.display_name().to_string(),
+        to: base_name(original.as_deref(), &member),
+    }))
+}
+
+/// Drop a member's nickname snapshot.
+pub async fn forget(pool: &DB, guild_id: GuildId, user_id: UserId) -> Result<()> {
+    query!(
+        r#"
+        delete
+        from nickname_snapshots
+        where guild_id = $1 and user_id = $2
+        "#,
+        guild_id.get() as i64,
+        user_id.get() as i64,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+async fn guild_owner(http: &impl CacheHttp, guild_id: GuildId) -> Result<UserId> {
+    if let Some(owner_id) = http
+        .cache()
+        .and_then(|cache| cache.guild(guild_id).map(|guild| guild.owner_id))
+    {
+        return Ok(owner_id);
+    }
+
+    Ok(guild_id.to_partial_guild(http).await?.owner_id)
+}
+
+/// Mark an active game as wanting to set a member's nickname.
+/// Claiming again moves the game to the front, since the most recent claim wins.
+pub async fn claim(pool: &DB, game: i32, guild_id: GuildId, user_id: UserId) -> Result<()> {
+    query!(
+        r#"
+        insert
+        into nickname_claims (game_id, user_id, guild_id)
+        values ($1, $2, $3)
+        on conflict (game_id, user_id) do update
+        set claimed_at = now() at time zone 'utc'
+        "#,
+        game,
+        user_id.get() as i64,
+        guild_id.get() as i64,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Withdraw a game's claim on a member's nickname.
+pub async fn unclaim(pool: &DB, game: i32, user_id: UserId) -> Result<()> {
+    query!(
+        r#"
+        delete
+        from nickname_claims
+        where game_id = $1 and user_id = $2
+        "#,
+        game,
+        user_id.get() as i64,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// A game's claim on a member's nickname.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claim {
+    pub game_id: i32,
+    /// The name of the member's character in the game.
+    pub character: String,
+}
+
+/// The most recent claim on a member's nickname among their active games, where they have a
+/// character. Claims made at the same time are settled by the newer game.
+pub async fn winning_claim(
+    conn: &mut PgConnection,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Option<Claim>> {
+    let winner = query_as!(
+        Claim,
+        r#"
+        select
+            c.game_id,
+            ch.name as "character"
+        from nickname_claims as c
+        join games as g on g.id = c.game_id
+        join players as p on p.game_id = c.game_id and p.user_id = c.user_id
+        join characters as ch on ch.id = p.character_id
+        where
+            c.guild_id = $1
+            and
+            c.user_id = $2
+            and
+            g.active_since is not null
+        order by c.claimed_at desc, c.game_id desc
+        limit 1
+        "#,
+        guild_id.get() as i64,
+        user_id.get() as i64,
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(winner)
+}
+
+/// Give a member the nickname from the most recent claim among their active games,
+/// or restore their original nickname if no active game claims it.
+pub async fn settle(
+    http: impl CacheHttp,
+    pool: &DB,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Option<Rename>> {
+    if guild_owner(&http, guild_id).await? == user_id {
+        return Ok(None);
+    }
+
+    let maybe_winner = winning_claim(&mut *pool.acquire().await?, guild_id, user_id).await?;
+
+    match maybe_winner {
+        Some(winner) => {
+            let template = template(pool, winner.game_id).await?;
+            apply(http, pool, guild_id, user_id, &template, &winner.character).await
+        }
+        None => restore(http, pool, guild_id, user_id).await,
+    }
+}
+
+/// Bring a member's nickname up to date with an active game, after they join or leave it,
+/// or gain or lose a character in it. Does nothing while the game is inactive.
+pub async fn refresh(
+    http: impl CacheHttp,
+    pool: &DB,
+    game: i32,
+    user_id: UserId,
+) -> Result<Option<Rename>> {
+    let record = query!(
+        r#"
+        select
+            guild_id,
+            active_since is not null as "active!",
+            exists (
+                select 1
+                from players
+                where
+                    game_id = g.id
+                    and
+                    user_id = $2
+                    and
+                    character_id is not null
+            ) as "has_character!"
+        from games as g
+        where id = $1
+        "#,
+        game,
+        user_id.get() as i64,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if !record.active {
+        return Ok(None);
+    }
+
+    let guild_id = GuildId::from(record.guild_id as u64);
+
+    if record.has_character {
+        claim(pool, game, guild_id, user_id).await?;
+    } else {
+        unclaim(pool, game, user_id).await?;
+    }
+
+    settle(http, pool, guild_id, user_id).await
+}
+
+/// What happened to one member's nickname during a bulk change.
+#[derive(Debug)]
+pub enum Outcome {
+    Changed(Rename),
+    Skipped(String),
+    Failed(String),
+}
+
+impl Outcome {
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Outcome::Failed(_))
+    }
+
+    pub fn describe(&self, user_id: UserId) -> String {
+        match self {
+            Outcome::Changed(rename) => format!(
+                "✅ {} `{}` → `{}`",
+                user_id.mention(),
+                rename.from,
+                rename.to
+            ),
+            Outcome::Skipped(reason) => format!("➖ {} skipped: {reason}", user_id.mention()),
+            Outcome::Failed(reason) => format!("❌ {} failed: {reason}", user_id.mention()),
+        }
+    }
+}
+
+/// A short explanation of why a nickname couldn't be changed.
+pub fn failure_reason(error: &Error) -> String {
+    match error {
+        Error::Discord(serenity::Error::Http(HttpError::UnsuccessfulRequest(response))) => {
+            match response.error.code {
+                10007 => "they aren't in the server".to_string(),
+                50013 => "I'm missing permission, their role may be higher than mine".to_string(),
+                _ => response.error.message.clone(),
+            }
+        }
+        error => error.to_string(),
+    }
+}
+
+/// [`settle`] many members at once, collecting what happened to each of them.
+pub async fn settle_all(
+    http: &impl CacheHttp,
+    pool: &DB,
+    guild_id: GuildId,
+    user_ids: Vec<UserId>,
+) -> Vec<(UserId, Outcome)> {
+    stream::iter(user_ids)
+        .map(|user_id| async move {
+            let outcome = match settle(http, pool, guild_id, user_id).await {
+                Ok(Some(rename)) => Outcome::Changed(rename),
+                Ok(None) => Outcome::Skipped("nothing to change".to_string()),
+                Err(e) => Outcome::Failed(failure_reason(&e)),
+            };
+            (user_id, outcome)
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await
+}
+
+/// Describe the outcome of [`refresh`] for a command's reply.
+pub fn describe(result: &Result<Option<Rename>>) -> Option<String> {
+    match result {
+        Ok(Some(rename)) => Some(format!(
+            "Nickname changed from `{}` to `{}`.",
+            rename.from, rename.to
+        )),
+        Ok(None) => None,
+        Err(e) => Some(format!(
+            "I couldn't update their nickname: {}",
+            failure_reason(e)
+        )),
+    }
+}
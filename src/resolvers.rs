@@ -0,0 +1,178 @@
+//! Reading a roll the way a system does.
+//!
+//! A system can choose one resolver, stored by [`Resolver::name`] in `systems.resolver`. When
+//! someone rolls in a game using that system, the resolver turns the dice into an outcome, like
+//! "Partial success". New resolvers only need to be added to [`RESOLVERS`].
+
+use crate::dice::{Group, Roll};
+
+pub trait Resolver: Sync {
+    /// The name stored for a system that uses this resolver.
+    fn name(&self) -> &'static str;
+
+    /// A short explanation of how rolls are read, for choosing a resolver.
+    fn description(&self) -> &'static str;
+
+    /// What the roll means, or `None` if it doesn't use this system's dice.
+    fn resolve(&self, roll: &Roll) -> Option<String>;
+
+    /// The dice to roll for a rating on its own, like `/roll dice @prowess`, in systems that roll pools.
+    fn pool(&self, _rating: i64) -> Option<String> {
+        None
+    }
+}
+
+/// Every resolver a system can choose.
+pub const RESOLVERS: &[&dyn Resolver] = &[&D20, &BladesInTheDark, &PoweredByTheApocalypse, &Fate];
+
+/// Find a resolver by its [`Resolver::name`].
+pub fn find(name: &str) -> Option<&'static dyn Resolver> {
+    RESOLVERS.iter().copied().find(|r| r.name() == name)
+}
+
+/// The values of the dice that count towards the total, across every group matching `filter`.
+fn kept<'a>(
+    roll: &'a Roll,
+    filter: impl Fn(&Group) -> bool + 'a,
+) -> impl Iterator<Item = i64> + 'a {
+    roll.groups
+        .iter()
+        .filter(move |g| filter(g))
+        .flat_map(|g| g.dice.iter().filter(|d| d.kept).map(|d| d.value))
+}
+
+fn is_d6(group: &Group) -> bool {
+    group.sides == 6 && !group.fate && !group.successes
+}
+
+/// Natural 20s and 1s on the first d20, as in D&D and its relatives.
+pub struct D20;
+
+impl Resolver for D20 {
+    fn name(&self) -> &'static str {
+        "d20"
+    }
+
+    fn description(&self) -> &'static str {
+        "A natural 20 on a d20 is a critical hit, and a natural 1 a critical miss"
+    }
+
+    fn resolve(&self, roll: &Roll) -> Option<String> {
+        let natural = kept(roll, |g| g.sides == 20 && !g.fate).next()?;
+        match natural {
+            20 => Some(format!("Critical hit! ({})", roll.total)),
+            1 => Some(format!("Critical miss! ({})", roll.total)),
+            _ => Some(format!("Natural {natural} ({})", roll.total)),
+        }
+    }
+}
+
+/// Blades in the Dark: the highest d6 decides, and more than one 6 is a critical.
+pub struct BladesInTheDark;
+
+impl Resolver for BladesInTheDark {
+    fn name(&self) -> &'static str {
+        "bitd"
+    }
+
+    fn description(&self) -> &'static str {
+        "Blades in the Dark: the highest d6 decides, and two or more 6s are a critical"
+    }
+
+    fn resolve(&self, roll: &Roll) -> Option<String> {
+        let dice: Vec<i64> = kept(roll, is_d6).collect();
+        let highest = dice.iter().copied().max()?;
+        let sixes = dice.iter().filter(|&&d| d == 6).count();
+        Some(
+            match (highest, sixes) {
+                (6, 2..) => "Critical success",
+                (6, _) => "Full success",
+                (4 | 5, _) => "Partial success",
+                _ => "Bad outcome",
+            }
+            .to_string(),
+        )
+    }
+
+    /// A rating of zero rolls two dice and keeps the lower.
+    fn pool(&self, rating: i64) -> Option<String> {
+        Some(if rating > 0 {
+            format!("{rating}d6")
+        } else {
+            "2d6kl".to_string()
+        })
+    }
+}
+
+/// Powered by the Apocalypse: 2d6 plus a stat, read in bands.
+pub struct PoweredByTheApocalypse;
+
+impl Resolver for PoweredByTheApocalypse {
+    fn name(&self) -> &'static str {
+        "pbta"
+    }
+
+    fn description(&self) -> &'static str {
+        "Powered by the Apocalypse: 2d6 plus a stat, where 10+ is a strong hit, 7-9 weak, and 6- a miss"
+    }
+
+    /// Only rolls of exactly one 2d6 are moves, so other d6 rolls like harm are left alone.
+    fn resolve(&self, roll: &Roll) -> Option<String> {
+        let mut d6 = roll.groups.iter().filter(|g| is_d6(g));
+        let (Some(group), None) = (d6.next(), d6.next()) else {
+            return None;
+        };
+        if group.dice.iter().filter(|d| d.kept).count() != 2 {
+            return None;
+        }
+        Some(
+            match roll.total {
+                10.. => "Strong hit",
+                7..=9 => "Weak hit",
+                _ => "Miss",
+            }
+            .to_string(),
+        )
+    }
+}
+
+/// Fate: 4dF plus a skill, named on the ladder.
+pub struct Fate;
+
+impl Fate {
+    const LADDER: [&'static str; 13] = [
+        "Horrifying",
+        "Catastrophic",
+        "Terrible",
+        "Poor",
+        "Mediocre",
+        "Average",
+        "Fair",
+        "Good",
+        "Great",
+        "Superb",
+        "Fantastic",
+        "Epic",
+        "Legendary",
+    ];
+
+    /// The ladder runs from -4 to +8. Results past either end are named after the end.
+    fn ladder(total: i64) -> &'static str {
+        Self::LADDER[(total.clamp(-4, 8) + 4) as usize]
+    }
+}
+
+impl Resolver for Fate {
+    fn name(&self) -> &'static str {
+        "fate"
+    }
+
+    fn description(&self) -> &'static str {
+        "Fate: 4dF plus a skill, named on the ladder from Horrifying (-4) to Legendary (+8)"
+    }
+
+    fn resolve(&self, roll: &Roll) -> Option<String> {
+        roll.groups.iter().find(|g| g.fate)?;
+        Some(format!("{} ({:+})", Self::ladder(roll.total), roll.total))
+    }
+}
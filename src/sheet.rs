@@ -0,0 +1,233 @@
+//! Custom character fields, defined per system.
+//!
+//! A system's schema is written one field per line, as `Name: type`:
+//!
+//! ```text
+//! Class: text
+//! Level: integer
+//! Playbook: choice(Cutter, Hound, Leech)
+//! Stress: 0-9
+//! ```
+//!
+//! Every game using the system gives its characters those fields. Values are stored as a JSON
+//! object from field name to value, and are checked against the schema whenever they're set.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The most fields a system can define, leaving room in a character embed for the built-in ones.
+pub const MAX_FIELDS: usize = 20;
+
+/// The longest a field name can be, which is the limit on modal labels.
+pub const MAX_NAME_LENGTH: usize = 45;
+
+/// The longest a text value can be, which is the limit on embed fields.
+pub const MAX_TEXT_LENGTH: usize = 1024;
+
+/// A character's values, by field name.
+pub type Values = Map<String, Value>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Field {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Kind {
+    Text,
+    Integer { min: Option<i64>, max: Option<i64> },
+    Choice { options: Vec<String> },
+}
+
+impl Kind {
+    /// Write the type the way a schema is written.
+    pub fn describe(&self) -> String {
+        match self {
+            Kind::Text => "text".to_string(),
+            Kind::Integer {
+                min: Some(min),
+                max: Some(max),
+            } => format!("{min}-{max}"),
+            Kind::Integer { .. } => "integer".to_string(),
+            Kind::Choice { options } => format!("choice({})", options.join(", ")),
+        }
+    }
+}
+
+fn parse_kind(kind: &str) -> Option<Kind> {
+    let lower = kind.to_lowercase();
+    match lower.as_str() {
+        "text" => return Some(Kind::Text),
+        "integer" | "number" => {
+            return Some(Kind::Integer {
+                min: None,
+                max: None,
+            })
+        }
+        _ => {}
+    }
+
+    if let Some(options) = lower
+        .starts_with("choice(")
+        .then(|| kind.get("choice(".len()..)?.strip_suffix(')'))
+        .flatten()
+    {
+        let options: Vec<String> = options
+            .split(',')
+            .map(|o| o.trim().to_string())
+            .filter(|o| !o.is_empty())
+            .collect();
+        return (!options.is_empty()).then_some(Kind::Choice { options });
+    }
+
+    // Skip the first character so that a negative minimum isn't mistaken for the dash.
+    let dash = kind.get(1..)?.find('-')? + 1;
+    let min = kind[..dash].trim().parse().ok()?;
+    let max = kind[dash + 1..].trim().parse().ok()?;
+    (min <= max).then_some(Kind::Integer {
+        min: Some(min),
+        max: Some(max),
+    })
+}
+
+/// Read a schema written one `Name: type` per line.
+pub fn parse_schema(text: &str) -> Result<Vec<Field>, Vec<String>> {
+    let mut fields: Vec<Field> = vec![];
+    let mut problems = vec![];
+
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let Some((name, kind)) = line.split_once(':') else {
+            problems.push(format!("`{line}` should look like `Name: type`."));
+            continue;
+        };
+        let name = name.trim();
+
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            problems.push(format!(
+                "`{line}`: names must be 1 to {MAX_NAME_LENGTH} characters."
+            ));
+            continue;
+        }
+        if fields.iter().any(|f| f.name.eq_ignore_ascii_case(name)) {
+            problems.push(format!("`{name}` is defined more than once."));
+            continue;
+        }
+
+        match parse_kind(kind.trim()) {
+            Some(kind) => fields.push(Field {
+                name: name.to_string(),
+                kind,
+            }),
+            None => problems.push(format!(
+                "`{}` isn't a type. Use `text`, `integer`, a range like `0-9`, or `choice(A, B)`.",
+                kind.trim()
+            )),
+        }
+    }
+
+    if fields.len() > MAX_FIELDS {
+        problems.push(format!("A system can have at most {MAX_FIELDS} fields."));
+    }
+
+    if problems.is_empty() {
+        Ok(fields)
+    } else {
+        Err(problems)
+    }
+}
+
+/// Write a schema back out in the form [`parse_schema`] reads.
+pub fn describe_schema(fields: &[Field]) -> String {
+    fields
+        .iter()
+        .map(|f| format!("{}: {}", f.name, f.kind.describe()))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Check a value typed in for a field. An empty value clears the field.
+pub fn validate(field: &Field, input: &str) -> Result<Option<Value>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    match &field.kind {
+        Kind::Text => {
+            if input.chars().count() > MAX_TEXT_LENGTH {
+                Err(format!(
+                    "{} must be at most {MAX_TEXT_LENGTH} characters.",
+                    field.name
+                ))
+            } else {
+                Ok(Some(Value::from(input)))
+            }
+        }
+        Kind::Integer { min, max } => {
+            let Ok(number) = input.parse::<i64>() else {
+                return Err(format!("{} must be a whole number.", field.name));
+            };
+            if min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max) {
+                Err(format!(
+                    "{} must be between {} and {}.",
+                    field.name,
+                    min.map_or("-∞".to_string(), |m| m.to_string()),
+                    max.map_or("∞".to_string(), |m| m.to_string()),
+                ))
+            } else {
+                Ok(Some(Value::from(number)))
+            }
+        }
+        Kind::Choice { options } => options
+            .iter()
+            .find(|o| o.eq_ignore_ascii_case(input))
+            .map(|o| Some(Value::from(o.as_str())))
+            .ok_or_else(|| format!("{} must be one of {}.", field.name, options.join(", "))),
+    }
+}
+
+/// Write a stored value the way it was typed in.
+pub fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// The values a character has for the fields in a schema, in schema order.
+///
+/// Values for fields that the schema no longer has are left out.
+pub fn render(fields: &[Field], values: &Values) -> Vec<(String, String)> {
+    fields
+        .iter()
+        .filter_map(|f| values.get(&f.name).map(|v| (f.name.clone(), display(v))))
+        .collect()
+}
+
+/// How rolls refer to a field, like `@action_rating` for "Action Rating".
+pub fn reference(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c == '_' => Some(c),
+            c if c.is_whitespace() || c == '-' => Some('_'),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The numbers a character has filled in, by [`reference`], for rolls to use.
+pub fn numbers(fields: &[Field], values: &Values) -> Vec<(String, i64)> {
+    fields
+        .iter()
+        .filter(|f| matches!(f.kind, Kind::Integer { .. }))
+        .filter_map(|f| {
+            let value = values.get(&f.name)?.as_i64()?;
+            Some((reference(&f.name), value))
+        })
+        .collect()
+}
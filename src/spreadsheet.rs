@@ -0,0 +1,192 @@
+//! Characters written as CSV, for moving rosters in from spreadsheets.
+//!
+//! The first row names the columns, in any order: `name`, `pronouns`, `description`, `image`
+//! and `player`. Only `name` is required. Empty cells are treated as missing.
+
+use std::collections::HashSet;
+
+use serenity::all::UserId;
+
+/// The columns a CSV may have.
+pub const COLUMNS: [&str; 5] = ["name", "pronouns", "description", "image", "player"];
+
+/// One character read from a CSV.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// The row number as a spreadsheet shows it, counting the header as row 1 and blank rows too.
+    pub number: usize,
+    pub name: String,
+    pub pronouns: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    /// Who should play the character, as written in the CSV.
+    pub player: Option<String>,
+}
+
+/// Split CSV text into records of fields, each with its row number as a spreadsheet shows it.
+///
+/// Fields may be quoted to hold commas, newlines or doubled `""` quotes, and a quoted newline
+/// doesn't start a new row. Blank rows are skipped, but still counted.
+pub fn records(text: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut row = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push((row, std::mem::take(&mut record)));
+                } else {
+                    record.clear();
+                }
+                row += 1;
+            }
+            c => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("A quoted cell is never closed.".to_string());
+    }
+
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push((row, record));
+    }
+
+    Ok(records)
+}
+
+/// Read characters from CSV text, checking them against the limits of `CharacterModal`.
+///
+/// Returns the valid rows, and a description of each problem found.
+pub fn parse(text: &str) -> (Vec<Row>, Vec<String>) {
+    let records = match records(text) {
+        Ok(records) => records,
+        Err(e) => return (vec![], vec![e]),
+    };
+
+    let Some(((_, header), records)) = records.split_first() else {
+        return (vec![], vec!["The file is empty.".to_string()]);
+    };
+
+    let header: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+    let mut problems = vec![];
+
+    for column in &header {
+        if !COLUMNS.contains(&column.as_str()) {
+            problems.push(format!(
+                "Unknown column `{column}`. The columns are {}.",
+                COLUMNS.map(|c| format!("`{c}`")).join(", ")
+            ));
+        }
+    }
+    if !header.iter().any(|h| h == "name") {
+        problems.push("There's no `name` column.".to_string());
+    }
+    if !problems.is_empty() {
+        return (vec![], problems);
+    }
+
+    let mut rows = vec![];
+    let mut names = HashSet::new();
+
+    for (number, record) in records {
+        let number = *number;
+        let cell = |column: &str| {
+            header
+                .iter()
+                .position(|h| h == column)
+                .and_then(|p| record.get(p))
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+        };
+
+        let row = Row {
+            number,
+            name: cell("name").unwrap_or_default(),
+            pronouns: cell("pronouns"),
+            description: cell("description"),
+            image: cell("image"),
+            player: cell("player"),
+        };
+
+        let mut row_problems = vec![];
+        if record.len() > header.len() {
+            row_problems.push(format!(
+                "has {} cells, but there are only {} columns",
+                record.len(),
+                header.len()
+            ));
+        }
+        match row.name.chars().count() {
+            0 => row_problems.push("has no name".to_string()),
+            1..3 => row_problems.push("the name must be at least 3 characters".to_string()),
+            33.. => row_problems.push("the name must be at most 32 characters".to_string()),
+            _ => {}
+        }
+        if row
+            .pronouns
+            .as_ref()
+            .is_some_and(|p| p.chars().count() > 20)
+        {
+            row_problems.push("the pronouns must be at most 20 characters".to_string());
+        }
+        if row
+            .description
+            .as_ref()
+            .is_some_and(|d| d.chars().count() > 1024)
+        {
+            row_problems.push("the description must be at most 1024 characters".to_string());
+        }
+        if row.image.as_ref().is_some_and(|i| i.chars().count() > 1024) {
+            row_problems.push("the image URL must be at most 1024 characters".to_string());
+        }
+        if !row.name.is_empty() && !names.insert(row.name.clone()) {
+            row_problems.push(format!("`{}` appears more than once", row.name));
+        }
+
+        if row_problems.is_empty() {
+            rows.push(row);
+        } else {
+            problems.push(format!("Row {number}: {}.", row_problems.join(", ")));
+        }
+    }
+
+    (rows, problems)
+}
+
+/// Read a user id from a player cell written as an id or a mention.
+pub fn user_id(player: &str) -> Option<UserId> {
+    let id = player
+        .strip_prefix("<@")
+        .and_then(|p| p.strip_suffix('>'))
+        .map(|p| p.strip_prefix('!').unwrap_or(p))
+        .unwrap_or(player);
+    id.parse::<u64>()
+        .ok()
+        .filter(|&id| id != 0)
+        .map(UserId::new)
+}
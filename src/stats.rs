@@ -0,0 +1,165 @@
+//! Summaries of the dice someone has rolled, for blaming them.
+//!
+//! Every die counts, including ones that were dropped or rerolled, since they were all rolled.
+//! Dice are grouped by type, so a `d20adv` and a `1d20 + 5` both add to the d20s.
+
+use std::ops::RangeInclusive;
+
+use crate::dice::Group;
+
+/// Faces are shown one at a time up to this many, and in bands past it.
+const MAX_BANDS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DieType {
+    Sides(u32),
+    Fate,
+}
+
+impl DieType {
+    pub fn of(group: &Group) -> Self {
+        if group.fate {
+            DieType::Fate
+        } else {
+            DieType::Sides(group.sides)
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            DieType::Sides(sides) => format!("d{sides}"),
+            DieType::Fate => "dF".to_string(),
+        }
+    }
+
+    pub fn faces(&self) -> RangeInclusive<i64> {
+        match self {
+            DieType::Sides(sides) => 1..=*sides as i64,
+            DieType::Fate => -1..=1,
+        }
+    }
+}
+
+/// Everything rolled on one type of die.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DieStats {
+    pub die: DieType,
+    /// How many times each face came up, from the lowest face.
+    pub counts: Vec<u64>,
+    /// The most dice in a row that landed above the middle face.
+    pub hot_streak: u64,
+    /// The most dice in a row that landed below the middle face.
+    pub cold_streak: u64,
+    hot: u64,
+    cold: u64,
+}
+
+impl DieStats {
+    fn new(die: DieType) -> Self {
+        DieStats {
+            die,
+            counts: vec![0; die.faces().count()],
+            hot_streak: 0,
+            cold_streak: 0,
+            hot: 0,
+            cold: 0,
+        }
+    }
+
+    fn add(&mut self, value: i64) {
+        let faces = self.die.faces();
+        if !faces.contains(&value) {
+            return;
+        }
+        self.counts[(value - faces.start()) as usize] += 1;
+
+        let doubled = value * 2;
+        let middle = faces.start() + faces.end();
+        if doubled > middle {
+            self.hot += 1;
+            self.cold = 0;
+        } else if doubled < middle {
+            self.cold += 1;
+            self.hot = 0;
+        } else {
+            self.hot = 0;
+            self.cold = 0;
+        }
+        self.hot_streak = self.hot_streak.max(self.hot);
+        self.cold_streak = self.cold_streak.max(self.cold);
+    }
+
+    pub fn rolled(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    pub fn average(&self) -> f64 {
+        let sum: i64 = self
+            .die
+            .faces()
+            .zip(&self.counts)
+            .map(|(face, &count)| face * count as i64)
+            .sum();
+        sum as f64 / self.rolled().max(1) as f64
+    }
+
+    /// The average a fair die would land on.
+    pub fn expected(&self) -> f64 {
+        let faces = self.die.faces();
+        (faces.start() + faces.end()) as f64 / 2.0
+    }
+
+    /// How many dice landed on their highest face, for dice that have crits.
+    pub fn crits(&self) -> Option<u64> {
+        self.has_crits().then(|| *self.counts.last().unwrap())
+    }
+
+    /// How many dice landed on their lowest face, for dice that have crits.
+    pub fn fumbles(&self) -> Option<u64> {
+        self.has_crits().then(|| self.counts[0])
+    }
+
+    fn has_crits(&self) -> bool {
+        matches!(self.die, DieType::Sides(sides) if sides > 1)
+    }
+
+    /// How many dice landed in each band of faces, with one face per band for dice that have
+    /// at most [`MAX_BANDS`] faces.
+    pub fn distribution(&self) -> Vec<(RangeInclusive<i64>, u64)> {
+        let start = *self.die.faces().start();
+        let width = self.counts.len().div_ceil(MAX_BANDS);
+        self.counts
+            .chunks(width)
+            .enumerate()
+            .map(|(i, counts)| {
+                let low = start + (i * width) as i64;
+                let high = low + counts.len() as i64 - 1;
+                (low..=high, counts.iter().sum())
+            })
+            .collect()
+    }
+}
+
+/// Sum up the dice in a series of rolls, given oldest first.
+///
+/// Returns the types of dice rolled, most rolled first.
+pub fn summarise<'a>(rolls: impl IntoIterator<Item = &'a [Group]>) -> Vec<DieStats> {
+    let mut stats: Vec<DieStats> = vec![];
+
+    for group in rolls.into_iter().flatten() {
+        let die = DieType::of(group);
+        let index = match stats.iter().position(|s| s.die == die) {
+            Some(index) => index,
+            None => {
+                stats.push(DieStats::new(die));
+                stats.len() - 1
+            }
+        };
+        for d in &group.dice {
+            stats[index].add(d.value);
+        }
+    }
+
+    stats.sort_by(|a, b| b.rolled().cmp(&a.rolled()).then(a.die.cmp(&b.die)));
+    stats
+}
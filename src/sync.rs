@@ -0,0 +1,258 @@
+use std::collections::HashSet;
+
+use serenity::all::{GuildId, Http, Member, RoleId, UserId};
+use sqlx::query;
+
+use crate::{Result, DB};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum SyncDirection {
+    #[name = "Give or take the role to match the player list"]
+    Role,
+    #[name = "Add or remove players to match the role"]
+    Players,
+}
+
+impl SyncDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncDirection::Role => "role",
+            SyncDirection::Players => "players",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "role" => Some(SyncDirection::Role),
+            "players" => Some(SyncDirection::Players),
+            _ => None,
+        }
+    }
+}
+
+/// The differences between a game's role and its player list.
+#[derive(Debug, Default)]
+pub struct Drift {
+    /// Players (and the owner) who don't have the game's role.
+    pub missing_role: Vec<UserId>,
+    /// Members who have the game's role but aren't players or the owner.
+    pub extra_role: Vec<UserId>,
+    /// Players who are no longer in the server.
+    pub departed: Vec<UserId>,
+}
+
+impl Drift {
+    pub fn is_empty(&self) -> bool {
+        self.missing_role.is_empty() && self.extra_role.is_empty() && self.departed.is_empty()
+    }
+}
+
+#[derive(Debug)]
+pub struct GameRoster {
+    pub id: i32,
+    pub guild_id: GuildId,
+    pub role_id: RoleId,
+    pub owner_id: UserId,
+    pub players: Vec<UserId>,
+}
+
+pub async fn roster(pool: &DB, game: i32) -> Result<GameRoster> {
+    let record = query!(
+        r#"
+        select
+            guild_id, role_id, owner_id,
+            array(select user_id from players where game_id = g.id) as "players!"
+        from games as g
+        where id = $1
+        "#,
+        game,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(GameRoster {
+        id: game,
+        guild_id: GuildId::from(record.guild_id as u64),
+        role_id: RoleId::from(record.role_id as u64),
+        owner_id: UserId::from(record.owner_id as u64),
+        players: record
+            .players
+            .into_iter()
+            .map(|p| UserId::from(p as u64))
+            .collect(),
+    })
+}
+
+/// Fetch every member of a guild, following Discord's pagination.
+pub async fn guild_members(http: &Http, guild_id: GuildId) -> Result<Vec<Member>> {
+    let mut members: Vec<Member> = vec![];
+
+    loop {
+        let page = guild_id
+            .members(http, Some(1000), members.last().map(|m| m.user.id))
+            .await?;
+        let done = page.len() < 1000;
+        members.extend(page);
+        if done {
+            return Ok(members);
+        }
+    }
+}
+
+pub fn drift(roster: &GameRoster, members: &[Member]) -> Drift {
+    let in_guild: HashSet<UserId> = members.iter().map(|m| m.user.id).collect();
+    let with_role: HashSet<UserId> = members
+        .iter()
+        .filter(|m| m.roles.contains(&roster.role_id))
+        .map(|m| m.user.id)
+        .collect();
+
+    let mut expected: HashSet<UserId> = roster.players.iter().copied().collect();
+    expected.insert(roster.owner_id);
+
+    let mut drift = Drift::default();
+
+    for user_id in &expected {
+        if !in_guild.contains(user_id) {
+            drift.departed.push(*user_id);
+        } else if !with_role.contains(user_id) {
+            drift.missing_role.push(*user_id);
+        }
+    }
+
+    for user_id in with_role {
+        if !expected.contains(&user_id) {
+            drift.extra_role.push(user_id);
+        }
+    }
+
+    drift
+}
+
+/// Resolve the drift of a game in the given direction.
+pub async fn fix(
+    http: &Http,
+    pool: &DB,
+    roster: &GameRoster,
+    drift: &Drift,
+    direction: SyncDirection,
+) -> Result<()> {
+    match direction {
+        SyncDirection::Role => {
+            for user_id in &drift.missing_role {
+                http.add_member_role(
+                    roster.guild_id,
+                    *user_id,
+                    roster.role_id,
+                    Some("Game role synced with player list"),
+                )
+                .await?;
+            }
+            for user_id in &drift.extra_role {
+                http.remove_member_role(
+                    roster.guild_id,
+                    *user_id,
+                    roster.role_id,
+                    Some("Game role synced with player list"),
+                )
+                .await?;
+            }
+        }
+        SyncDirection::Players => {
+            let added: Vec<i64> = drift.extra_role.iter().map(|u| u.get() as i64).collect();
+            let removed: Vec<i64> = drift
+                .missing_role
+                .iter()
+                .chain(drift.departed.iter())
+                .filter(|u| **u != roster.owner_id)
+                .map(|u| u.get() as i64)
+                .collect();
+
+            let mut txn = pool.begin().await?;
+
+            query!(
+                r#"
+                insert
+                into players (user_id, game_id)
+                select unnest($2::bigint[]), $1
+                on conflict do nothing
+                "#,
+                roster.id,
+                &added,
+            )
+            .execute(&mut *txn)
+            .await?;
+
+            query!(
+                r#"
+                delete
+                from players
+                where game_id = $1 and user_id = any($2)
+                "#,
+                roster.id,
+                &removed,
+            )
+            .execute(&mut *txn)
+            .await?;
+
+            txn.commit().await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check every game with automatic syncing enabled, fixing any drift in its saved direction.
+pub async fn sync_all(http: &Http, pool: &DB) -> Result<()> {
+    let games = query!(
+        r#"
+        select id, guild_id, auto_sync as "auto_sync!"
+        from games
+        where auto_sync is not null
+        order by guild_id
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut members: Option<(GuildId, Vec<Member>)> = None;
+
+    for game in games {
+        let direction = match SyncDirection::parse(&game.auto_sync) {
+            Some(direction) => direction,
+            None => continue,
+        };
+
+        let r: Result<()> = async {
+            let guild_id = GuildId::from(game.guild_id as u64);
+            if members.as_ref().map(|(g, _)| *g) != Some(guild_id) {
+                members = Some((guild_id, guild_members(http, guild_id).await?));
+            }
+            let (_, guild_members) = members.as_ref().unwrap();
+
+            let roster = roster(pool, game.id).await?;
+            let drift = drift(&roster, guild_members);
+
+            if !drift.is_empty() {
+                println!(
+                    "Syncing game {} by {}: {} missing role, {} extra role, {} departed",
+                    game.id,
+                    direction.as_str(),
+                    drift.missing_role.len(),
+                    drift.extra_role.len(),
+                    drift.departed.len(),
+                );
+                fix(http, pool, &roster, &drift, direction).await?;
+            }
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = r {
+            println!("Error while syncing game {}: {}", game.id, e);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use eurydice::announcements::parse_time;
+
+#[test]
+fn parse_time_in_utc() {
+    let time = parse_time("2024-10-31 19:30").unwrap();
+    assert_eq!(time.to_rfc3339(), "2024-10-31T19:30:00+00:00");
+}
+
+#[test]
+fn parse_time_ignores_surrounding_whitespace() {
+    assert!(parse_time("  2024-10-31 19:30 ").is_some());
+}
+
+#[test]
+fn parse_time_rejects_other_formats() {
+    assert!(parse_time("31/10/2024 19:30").is_none());
+    assert!(parse_time("2024-10-31").is_none());
+    assert!(parse_time("tomorrow").is_none());
+}
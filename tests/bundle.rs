@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+
+use eurydice::{
+    bundle::{self, Bundle, Character, Game, Player, System, VERSION},
+    sheet::{Field, Kind, Values},
+};
+use serenity::all::{ChannelId, RoleId, UserId};
+use sqlx::types::chrono::{TimeZone, Utc};
+
+fn example() -> Bundle {
+    Bundle {
+        version: VERSION,
+        game: Game {
+            title: "Blades in the Dark".to_string(),
+            abbreviation: "BitD".to_string(),
+            description: Some("Scoundrels in Doskvol.".to_string()),
+            image: None,
+            owner_id: UserId::new(80351110224678912),
+            role_id: RoleId::new(1034512349523456789),
+            main_channel_id: None,
+            channels: vec![],
+            nickname_template: None,
+            open: false,
+            created_at: Utc.with_ymd_and_hms(2024, 10, 31, 19, 30, 0).unwrap(),
+        },
+        system: Some(System {
+            title: "Blades in the Dark".to_string(),
+            abbreviation: "BitD".to_string(),
+            description: None,
+            image: None,
+            link: None,
+            fields: vec![Field {
+                name: "Playbook".to_string(),
+                kind: Kind::Choice {
+                    options: vec!["Cutter".to_string(), "Hound".to_string()],
+                },
+            }],
+            resolver: Some("bitd".to_string()),
+        }),
+        characters: vec![Character {
+            name: "Vex".to_string(),
+            pronouns: Some("she/her".to_string()),
+            description: None,
+            image: None,
+            author_id: UserId::new(80351110224678912),
+            fields: Values::from_iter([("Playbook".to_string(), "Cutter".into())]),
+        }],
+        players: vec![Player {
+            user_id: UserId::new(80351110224678913),
+            character: Some("Vex".to_string()),
+        }],
+        sessions: vec![],
+    }
+}
+
+#[test]
+fn round_trip() {
+    let bundle = example();
+    let json = serde_json::to_string(&bundle).unwrap();
+    assert_eq!(serde_json::from_str::<Bundle>(&json).unwrap(), bundle);
+}
+
+#[test]
+fn ids_are_strings() {
+    let json = serde_json::to_value(example()).unwrap();
+    assert_eq!(json["game"]["owner_id"], "80351110224678912");
+    assert_eq!(json["players"][0]["user_id"], "80351110224678913");
+}
+
+#[test]
+fn optional_fields_can_be_left_out() {
+    let json = r#"{
+        "version": 1,
+        "game": {
+            "title": "Blades in the Dark",
+            "abbreviation": "BitD",
+            "description": null,
+            "image": null,
+            "owner_id": "80351110224678912",
+            "role_id": "1034512349523456789",
+            "main_channel_id": null,
+            "nickname_template": null,
+            "created_at": "2024-10-31T19:30:00Z"
+        },
+        "system": null,
+        "characters": [],
+        "players": []
+    }"#;
+
+    let bundle: Bundle = serde_json::from_str(json).unwrap();
+    assert!(bundle.game.channels.is_empty());
+    assert!(!bundle.game.open);
+    assert!(bundle.sessions.is_empty());
+}
+
+#[test]
+fn markdown_summary() {
+    let markdown = bundle::markdown(&example());
+    assert!(markdown.starts_with("# Blades in the Dark [BitD]\n"));
+    assert!(markdown.contains("**System:** Blades in the Dark [BitD]"));
+    assert!(markdown.contains("Scoundrels in Doskvol."));
+    assert!(markdown.contains("- <@80351110224678913> as Vex"));
+    assert!(markdown.contains("### Vex (she/her)"));
+    assert!(markdown.contains("- **Playbook:** Cutter"));
+}
+
+#[test]
+fn conflicts() {
+    let mut bundle = example();
+    let titles = HashSet::from(["Blades in the Dark".to_string()]);
+    assert!(bundle::conflicts(&bundle, &HashSet::new(), &HashSet::new()).is_empty());
+    assert_eq!(
+        bundle::conflicts(&bundle, &titles, &HashSet::new()).len(),
+        1
+    );
+
+    bundle.version = VERSION + 1;
+    bundle.characters.push(bundle.characters[0].clone());
+    bundle.players[0].character = Some("Nobody".to_string());
+    assert_eq!(
+        bundle::conflicts(&bundle, &HashSet::new(), &HashSet::new()).len(),
+        3
+    );
+
+    bundle.players.push(bundle.players[0].clone());
+    let conflicts = bundle::conflicts(&bundle, &HashSet::new(), &HashSet::new());
+    assert!(conflicts
+        .contains(&"<@80351110224678913> is listed as a player more than once.".to_string()));
+}
+
+#[test]
+fn remap_to_server() {
+    let importer = UserId::new(1);
+    let mut bundle = example();
+    bundle.game.main_channel_id = Some(ChannelId::new(2));
+    bundle.players.push(Player {
+        user_id: importer,
+        character: None,
+    });
+
+    let members = HashSet::from([importer, UserId::new(80351110224678913)]);
+    let changes = bundle::remap(&mut bundle, importer, &members, &HashSet::new());
+
+    assert_eq!(changes.len(), 4);
+    assert_eq!(bundle.game.owner_id, importer);
+    assert_eq!(bundle.game.main_channel_id, None);
+    assert_eq!(bundle.characters[0].author_id, importer);
+    assert_eq!(bundle.players.len(), 1);
+    assert_eq!(bundle.players[0].user_id, UserId::new(80351110224678913));
+}
@@ -1,15 +1,22 @@
+#![allow(clippy::result_large_err)]
+
 use std::env;
 
 use dotenv::dotenv;
+use eurydice::{
+    commands::roll::{logged_rolls, Visibility},
+    nickname::winning_claim,
+};
+use serenity::all::{GuildId, UserId};
 use sqlx::{
-    migrate, migrate::MigrateDatabase, postgres::PgPoolOptions, query, Pool, Postgres, Transaction,
+    migrate, migrate::MigrateDatabase, postgres::PgPoolOptions, query, Postgres, Transaction,
 };
 use tokio::sync::OnceCell;
 
-static INIT: OnceCell<Pool<Postgres>> = OnceCell::const_new();
+static INIT: OnceCell<String> = OnceCell::const_new();
 
 async fn setup<'a>() -> eurydice::Result<Transaction<'a, Postgres>> {
-    Ok(INIT
+    let db_url = INIT
         .get_or_init(|| async {
             dotenv().unwrap();
 
@@ -21,16 +28,24 @@ async fn setup<'a>() -> eurydice::Result<Transaction<'a, Postgres>> {
             Postgres::create_database(&db_url).await.unwrap();
 
             let pool = PgPoolOptions::new()
-                .max_connections(5)
+                .max_connections(1)
                 .connect(&db_url)
                 .await
                 .unwrap();
 
             migrate!("./migrations").run(&pool).await.unwrap();
 
-            pool
+            pool.close().await;
+
+            db_url
         })
-        .await
+        .await;
+
+    // Every test runs on its own runtime, so each one gets its own pool.
+    Ok(PgPoolOptions::new()
+        .max_connections(1)
+        .connect(db_url)
+        .await?
         .begin()
         .await?)
 }
@@ -308,3 +323,572 @@ async fn unique_character() -> eurydice::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn game_auto_sync_direction() -> eurydice::Result<()> {
+    let mut txn = setup().await?;
+
+    let result = query!(
+        r#"
+        insert into games
+            (guild_id, owner_id, role_id, title, abbreviation, auto_sync)
+        values
+            ($1, $2, $3, $4, $5, $6)
+        "#,
+        0,
+        0,
+        0,
+        "Blades in the Dark",
+        "BitD",
+        "sideways",
+    )
+    .execute(&mut *txn)
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(sqlx::Error::Database(e)) if e.is_check_violation()
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unique_linked_channel() -> eurydice::Result<()> {
+    let mut txn = setup().await?;
+
+    let game_id = query!(
+        r#"
+        insert into games
+            (guild_id, owner_id, role_id, title, abbreviation)
+        values
+            ($1, $2, $3, $4, $5)
+        returning id
+        "#,
+        0,
+        0,
+        0,
+        "Blades in the Dark",
+        "BitD",
+    )
+    .fetch_one(&mut *txn)
+    .await?
+    .id;
+
+    query!(
+        r#"
+        insert into game_channels
+            (channel_id, game_id, guild_id, purpose)
+        values
+            ($1, $2, $3, $4)
+        "#,
+        0,
+        game_id,
+        0,
+        "OOC",
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    let result = query!(
+        r#"
+        insert into game_channels
+            (channel_id, game_id, guild_id, purpose)
+        values
+            ($1, $2, $3, $4)
+        "#,
+        0,
+        game_id,
+        0,
+        "Voice",
+    )
+    .execute(&mut *txn)
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation()
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn nickname_claims_deleted_with_game() -> eurydice::Result<()> {
+    let mut txn = setup().await?;
+
+    let game_id = query!(
+        r#"
+        insert into games
+            (guild_id, owner_id, role_id, title, abbreviation)
+        values
+            ($1, $2, $3, $4, $5)
+        returning id
+        "#,
+        0,
+        0,
+        0,
+        "Blades in the Dark",
+        "BitD",
+    )
+    .fetch_one(&mut *txn)
+    .await?
+    .id;
+
+    query!(
+        r#"
+        insert into nickname_claims
+            (game_id, user_id, guild_id)
+        values
+            ($1, $2, $3)
+        "#,
+        game_id,
+        0,
+        0,
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    query!(
+        r#"
+        delete from games
+        where id = $1
+        "#,
+        game_id,
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    let remaining = query!(
+        r#"
+        select count(*) as "count!"
+        from nickname_claims
+        where game_id = $1
+        "#,
+        game_id,
+    )
+    .fetch_one(&mut *txn)
+    .await?
+    .count;
+
+    assert_eq!(remaining, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn newest_nickname_claim_wins() -> eurydice::Result<()> {
+    let mut txn = setup().await?;
+
+    let mut games = vec![];
+    for (title, abbreviation, character, claimed) in [
+        ("Blades in the Dark", "BitD", "Cid", "2024-01-02"),
+        ("Masks", "Masks", "Nova", "2024-01-01"),
+    ] {
+        let game_id = query!(
+            r#"
+            insert into games
+                (guild_id, owner_id, role_id, title, abbreviation, active_since)
+            values
+                ($1, $2, $3, $4, $5, now())
+            returning id
+            "#,
+            1,
+            0,
+            0,
+            title,
+            abbreviation,
+        )
+        .fetch_one(&mut *txn)
+        .await?
+        .id;
+
+        query!(
+            r#"
+            with character as (
+                insert into characters
+                    (game_id, guild_id, author_id, name)
+                values
+                    ($1, $2, $3, $4)
+                returning id
+            )
+            insert into players
+                (user_id, game_id, character_id)
+            values
+                ($3, $1, (select id from character))
+            "#,
+            game_id,
+            1,
+            2,
+            character,
+        )
+        .execute(&mut *txn)
+        .await?;
+
+        query!(
+            r#"
+            insert into nickname_claims
+                (game_id, user_id, guild_id, claimed_at)
+            values
+                ($1, $2, $3, $4::text::timestamptz)
+            "#,
+            game_id,
+            2,
+            1,
+            claimed,
+        )
+        .execute(&mut *txn)
+        .await?;
+
+        games.push(game_id);
+    }
+
+    // The first game was created first but claimed the member last.
+    let winner = winning_claim(&mut txn, GuildId::new(1), UserId::new(2))
+        .await?
+        .unwrap();
+    assert_eq!(winner.game_id, games[0]);
+    assert_eq!(winner.character, "Cid");
+
+    query!(
+        r#"
+        delete from nickname_claims
+        where game_id = $1
+        "#,
+        games[0],
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    let winner = winning_claim(&mut txn, GuildId::new(1), UserId::new(2))
+        .await?
+        .unwrap();
+    assert_eq!(winner.game_id, games[1]);
+    assert_eq!(winner.character, "Nova");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn one_transfer_offer_per_game() -> eurydice::Result<()> {
+    let mut txn = setup().await?;
+
+    let game_id = query!(
+        r#"
+        insert into games
+            (guild_id, owner_id, role_id, title, abbreviation)
+        values
+            ($1, $2, $3, $4, $5)
+        returning id
+        "#,
+        0,
+        0,
+        0,
+        "Blades in the Dark",
+        "BitD",
+    )
+    .fetch_one(&mut *txn)
+    .await?
+    .id;
+
+    query!(
+        r#"
+        insert into transfer_offers
+            (game_id, owner_id, recipient_id, expires_at)
+        values
+            ($1, $2, $3, now())
+        "#,
+        game_id,
+        0,
+        1,
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    let result = query!(
+        r#"
+        insert into transfer_offers
+            (game_id, owner_id, recipient_id, expires_at)
+        values
+            ($1, $2, $3, now())
+        "#,
+        game_id,
+        0,
+        2,
+    )
+    .execute(&mut *txn)
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation()
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn one_invite_per_player() -> eurydice::Result<()> {
+    let mut txn = setup().await?;
+
+    let game_id = query!(
+        r#"
+        insert into games
+            (guild_id, owner_id, role_id, title, abbreviation)
+        values
+            ($1, $2, $3, $4, $5)
+        returning id
+        "#,
+        0,
+        0,
+        0,
+        "Blades in the Dark",
+        "BitD",
+    )
+    .fetch_one(&mut *txn)
+    .await?
+    .id;
+
+    query!(
+        r#"
+        insert into invites
+            (game_id, guild_id, user_id, invited_by)
+        values
+            ($1, $2, $3, $4)
+        "#,
+        game_id,
+        0,
+        1,
+        0,
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    let result = query!(
+        r#"
+        insert into invites
+            (game_id, guild_id, user_id, invited_by)
+        values
+            ($1, $2, $3, $4)
+        "#,
+        game_id,
+        0,
+        1,
+        2,
+    )
+    .execute(&mut *txn)
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation()
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unique_template_character_name() -> eurydice::Result<()> {
+    let mut txn = setup().await?;
+
+    let template_id = query!(
+        r#"
+        insert into game_templates
+            (guild_id, name)
+        values
+            ($1, $2)
+        returning id
+        "#,
+        0,
+        "Convention One-Shot",
+    )
+    .fetch_one(&mut *txn)
+    .await?
+    .id;
+
+    query!(
+        r#"
+        insert into template_characters
+            (template_id, name)
+        values
+            ($1, $2)
+        "#,
+        template_id,
+        "Pregen",
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    let result = query!(
+        r#"
+        insert into template_characters
+            (template_id, name)
+        values
+            ($1, $2)
+        "#,
+        template_id,
+        "Pregen",
+    )
+    .execute(&mut *txn)
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation()
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rolls_outlive_their_character() -> eurydice::Result<()> {
+    let mut txn = setup().await?;
+
+    let game_id = query!(
+        r#"
+        insert into games
+            (guild_id, owner_id, role_id, title, abbreviation)
+        values
+            ($1, $2, $3, $4, $5)
+        returning id
+        "#,
+        0,
+        0,
+        0,
+        "Blades in the Dark",
+        "BitD",
+    )
+    .fetch_one(&mut *txn)
+    .await?
+    .id;
+
+    let character_id = query!(
+        r#"
+        insert into characters
+            (guild_id, author_id, game_id, name)
+        values
+            ($1, $2, $3, $4)
+        returning id
+        "#,
+        0,
+        0,
+        game_id,
+        "Vex",
+    )
+    .fetch_one(&mut *txn)
+    .await?
+    .id;
+
+    query!(
+        r#"
+        insert into rolls
+            (game_id, user_id, character_id, expression, total, groups)
+        values
+            ($1, $2, $3, $4, $5, $6)
+        "#,
+        game_id,
+        0,
+        character_id,
+        "2d6",
+        7,
+        serde_json::json!([]),
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    query!(
+        r#"
+        delete from characters
+        where id = $1
+        "#,
+        character_id,
+    )
+    .execute(&mut *txn)
+    .await?;
+
+    let roll = query!(
+        r#"
+        select character_id
+        from rolls
+        where game_id = $1
+        "#,
+        game_id,
+    )
+    .fetch_one(&mut *txn)
+    .await?;
+
+    assert_eq!(roll.character_id, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hidden_rolls_are_left_out_of_the_log() -> eurydice::Result<()> {
+    let mut txn = setup().await?;
+
+    let game_id = query!(
+        r#"
+        insert into games
+            (guild_id, owner_id, role_id, title, abbreviation)
+        values
+            ($1, $2, $3, $4, $5)
+        returning id
+        "#,
+        0,
+        0,
+        0,
+        "Blades in the Dark",
+        "BitD",
+    )
+    .fetch_one(&mut *txn)
+    .await?
+    .id;
+
+    for (user_id, visibility) in [(1, "public"), (1, "gm"), (1, "self"), (2, "gm")] {
+        query!(
+            r#"
+            insert into rolls
+                (game_id, user_id, expression, total, groups, visibility)
+            values
+                ($1, $2, $3, $4, $5, $6)
+            "#,
+            game_id,
+            user_id,
+            "2d6",
+            7,
+            serde_json::json!([]),
+            visibility,
+        )
+        .execute(&mut *txn)
+        .await?;
+    }
+
+    let mut visible = vec![];
+    for (user_id, manager) in [(1, false), (2, false), (3, true)] {
+        let rolls = logged_rolls()
+            .conn(&mut txn)
+            .game(game_id)
+            .viewer(UserId::new(user_id))
+            .manager(manager)
+            .limit(100)
+            .call()
+            .await?;
+        visible.push(rolls.len());
+    }
+
+    let only_player_2 = logged_rolls()
+        .conn(&mut txn)
+        .game(game_id)
+        .viewer(UserId::new(3))
+        .manager(true)
+        .player(UserId::new(2))
+        .limit(100)
+        .call()
+        .await?;
+    assert_eq!(only_player_2.len(), 1);
+    assert_eq!(only_player_2[0].visibility, Visibility::Gm);
+
+    assert_eq!(visible, [3, 2, 3]);
+
+    Ok(())
+}
@@ -0,0 +1,257 @@
+use eurydice::dice::{self, DiceError, Die, Group};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(46)
+}
+
+fn kept(group: &Group) -> Vec<i64> {
+    group
+        .dice
+        .iter()
+        .filter(|d| d.kept)
+        .map(|d| d.value)
+        .collect()
+}
+
+#[test]
+fn arithmetic() {
+    let mut rng = rng();
+    assert_eq!(dice::roll("2 + 3 * (4 - 1)", &mut rng).unwrap().total, 11);
+    assert_eq!(dice::roll("-7 / 2", &mut rng).unwrap().total, -4);
+    assert_eq!(
+        dice::roll("1 / (2 - 2)", &mut rng),
+        Err(DiceError::DivideByZero)
+    );
+}
+
+#[test]
+fn sums_dice_and_modifiers() {
+    let mut rng = rng();
+    for _ in 0..100 {
+        let roll = dice::roll("3D6 + d% - 2", &mut rng).unwrap();
+        assert_eq!(roll.groups.len(), 2);
+        assert_eq!(roll.groups[0].label, "3d6");
+        assert!(roll.groups[0]
+            .dice
+            .iter()
+            .all(|d| (1..=6).contains(&d.value)));
+        assert!((1..=100).contains(&roll.groups[1].total));
+        assert_eq!(roll.total, roll.groups[0].total + roll.groups[1].total - 2);
+    }
+}
+
+#[test]
+fn fate_dice() {
+    let mut rng = rng();
+    for _ in 0..50 {
+        let roll = dice::roll("4dF + 2", &mut rng).unwrap();
+        let group = &roll.groups[0];
+        assert!(group.fate);
+        assert_eq!(group.dice.len(), 4);
+        assert!(group.dice.iter().all(|d| (-1..=1).contains(&d.value)));
+        assert_eq!(roll.total, group.total + 2);
+    }
+    assert_eq!(
+        dice::parse("dfr<1"),
+        Err(DiceError::Forever("dfr<1".to_string()))
+    );
+}
+
+#[test]
+fn keep_and_drop() {
+    let mut rng = rng();
+    for (expression, kept_count) in [
+        ("4d6kh3", 3),
+        ("4d6k", 1),
+        ("4d6kl2", 2),
+        ("4d6dl", 3),
+        ("4d6dh3", 1),
+    ] {
+        let roll = dice::roll(expression, &mut rng).unwrap();
+        let group = &roll.groups[0];
+        let mut values: Vec<i64> = group.dice.iter().map(|d| d.value).collect();
+        values.sort_unstable_by(|a, b| b.cmp(a));
+        let expected: i64 = if expression.contains("kl") || expression.contains("dh") {
+            values[4 - kept_count..].iter().sum()
+        } else {
+            values[..kept_count].iter().sum()
+        };
+        assert_eq!(kept(group).len(), kept_count, "{expression}");
+        assert_eq!(group.total, expected, "{expression}");
+    }
+}
+
+#[test]
+fn advantage() {
+    let mut rng = rng();
+    for _ in 0..50 {
+        let roll = dice::roll("d20 adv", &mut rng).unwrap();
+        let group = &roll.groups[0];
+        assert_eq!(group.dice.len(), 2);
+        assert_eq!(
+            group.total,
+            group.dice.iter().map(|d| d.value).max().unwrap()
+        );
+
+        let roll = dice::roll("d20dis", &mut rng).unwrap();
+        let group = &roll.groups[0];
+        assert_eq!(
+            group.total,
+            group.dice.iter().map(|d| d.value).min().unwrap()
+        );
+    }
+    assert_eq!(dice::parse("2d20adv"), Err(DiceError::Advantage));
+    assert_eq!(dice::parse("d20adv kh1"), Err(DiceError::AdvantageKeep));
+    assert_eq!(dice::parse("d20dis dl1"), Err(DiceError::AdvantageKeep));
+    assert_eq!(dice::parse("d20kh1 adv"), Err(DiceError::AdvantageKeep));
+}
+
+#[test]
+fn exploding() {
+    let mut rng = rng();
+    let roll = dice::roll("50d6!", &mut rng).unwrap();
+    let group = &roll.groups[0];
+    let exploded = group.dice.iter().filter(|d| d.exploded).count();
+    assert!(exploded > 0);
+    assert_eq!(group.dice.len(), 50 + exploded);
+    assert!(group.dice.iter().all(|d| d.exploded == (d.value == 6)));
+
+    let roll = dice::roll("50d6!>5", &mut rng).unwrap();
+    assert!(roll.groups[0]
+        .dice
+        .iter()
+        .all(|d| d.exploded == (d.value >= 5)));
+}
+
+#[test]
+fn rerolls() {
+    let mut rng = rng();
+    let roll = dice::roll("50d6r<2", &mut rng).unwrap();
+    let group = &roll.groups[0];
+    assert_eq!(kept(group).len(), 50);
+    assert!(kept(group).iter().all(|&v| v > 2));
+    assert!(group
+        .dice
+        .iter()
+        .filter(|d| d.rerolled)
+        .all(|d| d.value <= 2 && !d.kept));
+
+    let roll = dice::roll("50d6ro1", &mut rng).unwrap();
+    let group = &roll.groups[0];
+    let rerolled = group.dice.iter().filter(|d| d.rerolled).count();
+    assert_eq!(group.dice.len(), 50 + rerolled);
+}
+
+#[test]
+fn successes() {
+    let mut rng = rng();
+    let roll = dice::roll("10d10>7", &mut rng).unwrap();
+    let group = &roll.groups[0];
+    assert!(group.successes);
+    assert_eq!(
+        group.total,
+        group.dice.iter().filter(|d| d.value >= 7).count() as i64
+    );
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        dice::parse("2d"),
+        Err(DiceError::Expected {
+            expected: "a number of sides",
+            position: 3
+        })
+    );
+    assert_eq!(
+        dice::parse("2d6 +"),
+        Err(DiceError::Expected {
+            expected: "a number or dice",
+            position: 6
+        })
+    );
+    assert!(matches!(
+        dice::parse("(2d6"),
+        Err(DiceError::Expected { .. })
+    ));
+    assert!(matches!(
+        dice::parse("2d6 x"),
+        Err(DiceError::Expected { .. })
+    ));
+    assert_eq!(dice::parse("101d6"), Err(DiceError::DiceCount));
+    assert_eq!(dice::parse("0d6"), Err(DiceError::DiceCount));
+    assert_eq!(dice::parse("d1001"), Err(DiceError::Sides));
+    assert_eq!(
+        dice::parse("d6r<6"),
+        Err(DiceError::Forever("d6r<6".to_string()))
+    );
+    assert_eq!(
+        dice::parse("d1!"),
+        Err(DiceError::Forever("d1!".to_string()))
+    );
+    assert_eq!(
+        dice::parse("4d6kh1kl1"),
+        Err(DiceError::Repeated("keep and drop"))
+    );
+    assert_eq!(dice::parse("99999999999999999999"), Err(DiceError::TooBig));
+}
+
+#[test]
+fn describe() {
+    let die = |value, kept, exploded, rerolled| Die {
+        value,
+        kept,
+        exploded,
+        rerolled,
+    };
+    let group = Group {
+        label: "3d6!kh2".to_string(),
+        sides: 6,
+        fate: false,
+        dice: vec![
+            die(1, false, false, true),
+            die(6, true, true, false),
+            die(4, true, false, false),
+            die(2, false, false, false),
+        ],
+        successes: false,
+        total: 10,
+    };
+    assert_eq!(dice::describe(&group), "[~~1~~, 6!, 4, ~~2~~] = 10");
+
+    let group = Group {
+        successes: true,
+        total: 1,
+        ..group
+    };
+    assert!(dice::describe(&group).ends_with("= 1 success"));
+}
+
+#[test]
+fn references() {
+    assert_eq!(
+        dice::references("1d20 + @Str - @action_rating*2 + @"),
+        vec!["str", "action_rating"]
+    );
+
+    let lookup = |name: &str| (name == "str").then_some(3);
+    let mut rng = rng();
+    let roll = dice::parse_with("@STR * 2 + 1", &lookup)
+        .unwrap()
+        .roll(&mut rng)
+        .unwrap();
+    assert_eq!(roll.total, 7);
+    assert_eq!(
+        dice::parse_with("1d20 + @dex", &lookup),
+        Err(DiceError::UnknownReference("dex".to_string()))
+    );
+    assert_eq!(
+        dice::parse("@str"),
+        Err(DiceError::UnknownReference("str".to_string()))
+    );
+    assert!(matches!(
+        dice::parse_with("1 + @", &lookup),
+        Err(DiceError::Expected { .. })
+    ));
+}
@@ -0,0 +1,16 @@
+use eurydice::commands::game::parse_colour;
+use serenity::all::Colour;
+
+#[test]
+fn parse_colours() {
+    assert_eq!(parse_colour("#e91e63"), Some(Colour::new(0xe91e63)));
+    assert_eq!(parse_colour("E91E63"), Some(Colour::new(0xe91e63)));
+    assert_eq!(parse_colour(" #000000 "), Some(Colour::new(0)));
+}
+
+#[test]
+fn reject_invalid_colours() {
+    assert_eq!(parse_colour("#fff"), None);
+    assert_eq!(parse_colour("pink"), None);
+    assert_eq!(parse_colour("#e91e63ff"), None);
+}
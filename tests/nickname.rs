@@ -0,0 +1,88 @@
+use eurydice::nickname::{render, validate, TemplateError, DEFAULT_TEMPLATE, MAX_LENGTH};
+
+#[test]
+fn valid_templates() {
+    assert_eq!(validate(DEFAULT_TEMPLATE), Ok(()));
+    assert_eq!(validate("{character}"), Ok(()));
+    assert_eq!(validate("{character} [{player}]"), Ok(()));
+}
+
+#[test]
+fn invalid_templates() {
+    assert_eq!(validate("{player}"), Err(TemplateError::MissingCharacter));
+    assert_eq!(
+        validate("{character} {pronouns}"),
+        Err(TemplateError::UnknownPlaceholder("pronouns".to_string()))
+    );
+    assert_eq!(
+        validate("{character} {player"),
+        Err(TemplateError::Unclosed)
+    );
+    assert_eq!(
+        validate("{character} the very long and verbose epithet"),
+        Err(TemplateError::TooLong)
+    );
+}
+
+#[test]
+fn render_short_names() {
+    assert_eq!(render(DEFAULT_TEMPLATE, "Cid", "Drowrin"), "Drowrin (Cid)");
+    assert_eq!(render("{character}", "Cid", "Drowrin"), "Cid");
+    assert_eq!(
+        render("{character} [{player}]", "Cid", "Drowrin"),
+        "Cid [Drowrin]"
+    );
+}
+
+#[test]
+fn render_leaves_placeholders_in_names() {
+    assert_eq!(
+        render(DEFAULT_TEMPLATE, "{player}", "Drowrin"),
+        "Drowrin ({player})"
+    );
+    assert_eq!(
+        render("{character} [{player}]", "Cid", "{character}"),
+        "Cid [{character}]"
+    );
+}
+
+#[test]
+fn render_uses_first_name() {
+    assert_eq!(
+        render(DEFAULT_TEMPLATE, "Aloysius Percival Thorne", "Drowrin"),
+        "Drowrin (Aloysius)"
+    );
+}
+
+#[test]
+fn render_shortens_player_first() {
+    let nickname = render(
+        DEFAULT_TEMPLATE,
+        "Aloysius Thorne",
+        "Extraordinarilylongusername",
+    );
+    assert_eq!(nickname, "Extraordinarilylongu… (Aloysius)");
+    assert!(nickname.chars().count() <= MAX_LENGTH);
+}
+
+#[test]
+fn render_truncates_character() {
+    let nickname = render(
+        "{character}",
+        "Wolfeschlegelsteinhausenbergerdorff",
+        "Drowrin",
+    );
+    assert_eq!(nickname.chars().count(), MAX_LENGTH);
+    assert!(nickname.ends_with('…'));
+}
+
+#[test]
+fn render_never_exceeds_limit() {
+    let nickname = render(
+        "{character} [{player}]",
+        "Wolfeschlegelsteinhausenbergerdorff",
+        "Extraordinarilylongusername",
+    );
+    assert!(nickname.chars().count() <= MAX_LENGTH);
+    assert!(nickname.starts_with("Wolfe"));
+}
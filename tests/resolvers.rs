@@ -0,0 +1,178 @@
+use eurydice::{
+    dice::{Die, Group, Roll},
+    resolvers::{self, BladesInTheDark, Fate, PoweredByTheApocalypse, Resolver, D20},
+};
+
+fn group(sides: u32, values: &[i64]) -> Group {
+    Group {
+        label: format!("{}d{sides}", values.len()),
+        sides,
+        fate: false,
+        dice: values
+            .iter()
+            .map(|&value| Die {
+                value,
+                kept: true,
+                exploded: false,
+                rerolled: false,
+            })
+            .collect(),
+        successes: false,
+        total: values.iter().sum(),
+    }
+}
+
+fn roll(groups: Vec<Group>, modifier: i64) -> Roll {
+    Roll {
+        total: groups.iter().map(|g| g.total).sum::<i64>() + modifier,
+        groups,
+    }
+}
+
+fn fate(values: &[i64], modifier: i64) -> Roll {
+    let mut group = group(3, values);
+    group.fate = true;
+    roll(vec![group], modifier)
+}
+
+#[test]
+fn find_by_name() {
+    for resolver in resolvers::RESOLVERS {
+        assert_eq!(
+            resolvers::find(resolver.name()).unwrap().name(),
+            resolver.name()
+        );
+        assert!(resolver.description().chars().count() <= 100);
+    }
+    assert!(resolvers::find("gurps").is_none());
+}
+
+#[test]
+fn d20() {
+    let resolve = |values: &[i64], modifier| D20.resolve(&roll(vec![group(20, values)], modifier));
+    assert_eq!(resolve(&[20], 5).unwrap(), "Critical hit! (25)");
+    assert_eq!(resolve(&[1], 5).unwrap(), "Critical miss! (6)");
+    assert_eq!(resolve(&[12], 5).unwrap(), "Natural 12 (17)");
+
+    // With advantage, only the kept die counts.
+    let mut advantage = group(20, &[1, 20]);
+    advantage.dice[0].kept = false;
+    advantage.total = 20;
+    assert_eq!(
+        D20.resolve(&roll(vec![advantage], 0)).unwrap(),
+        "Critical hit! (20)"
+    );
+
+    assert_eq!(D20.resolve(&roll(vec![group(6, &[6])], 0)), None);
+}
+
+#[test]
+fn blades_in_the_dark() {
+    let resolve = |values: &[i64]| BladesInTheDark.resolve(&roll(vec![group(6, values)], 0));
+    assert_eq!(resolve(&[6, 6, 2]).unwrap(), "Critical success");
+    assert_eq!(resolve(&[6, 3]).unwrap(), "Full success");
+    assert_eq!(resolve(&[6]).unwrap(), "Full success");
+    assert_eq!(resolve(&[5, 1]).unwrap(), "Partial success");
+    assert_eq!(resolve(&[4]).unwrap(), "Partial success");
+    assert_eq!(resolve(&[3, 2, 1]).unwrap(), "Bad outcome");
+
+    // A zero-dice roll is 2d6 keeping the lower, so a dropped 6 doesn't count.
+    let mut zero = group(6, &[6, 6]);
+    zero.dice[1].kept = false;
+    assert_eq!(
+        BladesInTheDark.resolve(&roll(vec![zero], 0)).unwrap(),
+        "Full success"
+    );
+
+    assert_eq!(
+        BladesInTheDark.resolve(&roll(vec![group(20, &[6])], 0)),
+        None
+    );
+}
+
+#[test]
+fn dice_pools() {
+    assert_eq!(BladesInTheDark.pool(3).unwrap(), "3d6");
+    assert_eq!(BladesInTheDark.pool(0).unwrap(), "2d6kl");
+    assert_eq!(D20.pool(3), None);
+    assert_eq!(PoweredByTheApocalypse.pool(3), None);
+    assert_eq!(Fate.pool(3), None);
+}
+
+#[test]
+fn powered_by_the_apocalypse() {
+    let resolve =
+        |values: &[i64], stat| PoweredByTheApocalypse.resolve(&roll(vec![group(6, values)], stat));
+    assert_eq!(resolve(&[6, 6], 0).unwrap(), "Strong hit");
+    assert_eq!(resolve(&[5, 3], 2).unwrap(), "Strong hit");
+    assert_eq!(resolve(&[5, 4], 0).unwrap(), "Weak hit");
+    assert_eq!(resolve(&[3, 3], 1).unwrap(), "Weak hit");
+    assert_eq!(resolve(&[3, 3], 0).unwrap(), "Miss");
+    assert_eq!(resolve(&[1, 1], -1).unwrap(), "Miss");
+    assert_eq!(PoweredByTheApocalypse.resolve(&roll(vec![], 10)), None);
+}
+
+#[test]
+fn powered_by_the_apocalypse_only_reads_2d6() {
+    let resolve = |groups| PoweredByTheApocalypse.resolve(&roll(groups, 0));
+    assert_eq!(resolve(vec![group(6, &[4])]), None);
+    assert_eq!(resolve(vec![group(6, &[6, 5, 1])]), None);
+    assert_eq!(resolve(vec![group(6, &[3]), group(6, &[5])]), None);
+
+    let mut dropped = group(6, &[6, 5, 1]);
+    dropped.dice[2].kept = false;
+    dropped.total = 11;
+    assert_eq!(resolve(vec![dropped]).unwrap(), "Strong hit");
+}
+
+#[test]
+fn fate_ladder() {
+    let ladder = [
+        "Horrifying",
+        "Catastrophic",
+        "Terrible",
+        "Poor",
+        "Mediocre",
+        "Average",
+        "Fair",
+        "Good",
+        "Great",
+        "Superb",
+        "Fantastic",
+        "Epic",
+        "Legendary",
+    ];
+    for (total, name) in (-4..=8).zip(ladder) {
+        assert_eq!(
+            Fate.resolve(&fate(&[0, 0, 0, 0], total)).unwrap(),
+            format!("{name} ({total:+})")
+        );
+    }
+
+    assert_eq!(
+        Fate.resolve(&fate(&[1, 1, 1, 1], 4)).unwrap(),
+        "Legendary (+8)"
+    );
+    assert_eq!(
+        Fate.resolve(&fate(&[1, 1, 1, 1], 6)).unwrap(),
+        "Legendary (+10)"
+    );
+    assert_eq!(Fate.resolve(&fate(&[1, 0, 0, 1], 2)).unwrap(), "Great (+4)");
+    assert_eq!(
+        Fate.resolve(&fate(&[1, -1, 0, 0], 0)).unwrap(),
+        "Mediocre (+0)"
+    );
+    assert_eq!(
+        Fate.resolve(&fate(&[-1, -1, 0, 0], 0)).unwrap(),
+        "Terrible (-2)"
+    );
+    assert_eq!(
+        Fate.resolve(&fate(&[-1, -1, -1, -1], 0)).unwrap(),
+        "Horrifying (-4)"
+    );
+    assert_eq!(
+        Fate.resolve(&fate(&[-1, -1, -1, -1], -2)).unwrap(),
+        "Horrifying (-6)"
+    );
+    assert_eq!(Fate.resolve(&roll(vec![group(6, &[3])], 0)), None);
+}
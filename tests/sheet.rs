@@ -0,0 +1,103 @@
+use eurydice::sheet::{self, Field, Kind, Values};
+use serde_json::Value;
+
+fn schema() -> Vec<Field> {
+    sheet::parse_schema(
+        "Class: text\nLevel: integer\n\nPlaybook: choice(Cutter, Hound, Leech)\nStress: 0-9\nHeat: -3-3",
+    )
+    .unwrap()
+}
+
+#[test]
+fn parse_schema() {
+    let fields = schema();
+    assert_eq!(fields.len(), 5);
+    assert_eq!(
+        fields[2].kind,
+        Kind::Choice {
+            options: vec![
+                "Cutter".to_string(),
+                "Hound".to_string(),
+                "Leech".to_string()
+            ],
+        }
+    );
+    assert_eq!(
+        fields[4].kind,
+        Kind::Integer {
+            min: Some(-3),
+            max: Some(3),
+        }
+    );
+    assert_eq!(
+        sheet::parse_schema(&sheet::describe_schema(&fields)).unwrap(),
+        fields
+    );
+}
+
+#[test]
+fn schema_problems() {
+    let problems = sheet::parse_schema("Class\nLevel: dice\nStress: 9-0\nclass: text\nClass: text")
+        .unwrap_err();
+    assert_eq!(problems.len(), 4);
+}
+
+#[test]
+fn stored_as_json() {
+    let json = serde_json::to_value(&schema()[3]).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "name": "Stress", "type": "integer", "min": 0, "max": 9 })
+    );
+}
+
+#[test]
+fn validate() {
+    let fields = schema();
+    assert_eq!(
+        sheet::validate(&fields[0], " Cutter "),
+        Ok(Some(Value::from("Cutter")))
+    );
+    assert_eq!(sheet::validate(&fields[0], "  "), Ok(None));
+    assert_eq!(sheet::validate(&fields[1], "3"), Ok(Some(Value::from(3))));
+    assert!(sheet::validate(&fields[1], "three").is_err());
+    assert_eq!(
+        sheet::validate(&fields[2], "hound"),
+        Ok(Some(Value::from("Hound")))
+    );
+    assert!(sheet::validate(&fields[2], "Whisper").is_err());
+    assert!(sheet::validate(&fields[3], "10").is_err());
+}
+
+#[test]
+fn render_in_schema_order() {
+    let values = Values::from_iter([
+        ("Stress".to_string(), Value::from(2)),
+        ("Class".to_string(), Value::from("Cutter")),
+        ("Removed".to_string(), Value::from("gone")),
+    ]);
+    assert_eq!(
+        sheet::render(&schema(), &values),
+        vec![
+            ("Class".to_string(), "Cutter".to_string()),
+            ("Stress".to_string(), "2".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn numbers_for_rolls() {
+    let fields =
+        sheet::parse_schema("Class: text\nAction Rating: 0-4\nStr: integer\nLevel: integer")
+            .unwrap();
+    let values = Values::from_iter([
+        ("Class".to_string(), Value::from("Cutter")),
+        ("Action Rating".to_string(), Value::from(2)),
+        ("Str".to_string(), Value::from(-1)),
+    ]);
+    assert_eq!(sheet::reference("Action Rating"), "action_rating");
+    assert_eq!(
+        sheet::numbers(&fields, &values),
+        vec![("action_rating".to_string(), 2), ("str".to_string(), -1)]
+    );
+}
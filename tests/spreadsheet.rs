@@ -0,0 +1,108 @@
+use eurydice::spreadsheet::{self, Row};
+use serenity::all::UserId;
+
+#[test]
+fn quoted_cells() {
+    let text =
+        "name,description\r\n\"Vex, the Whisper\",\"Says \"\"hush\"\"\nthen leaves\"\r\n\r\n";
+    assert_eq!(
+        spreadsheet::records(text).unwrap(),
+        vec![
+            (1, vec!["name".to_string(), "description".to_string()]),
+            (
+                2,
+                vec![
+                    "Vex, the Whisper".to_string(),
+                    "Says \"hush\"\nthen leaves".to_string()
+                ]
+            ),
+        ]
+    );
+    assert!(spreadsheet::records("name\n\"Vex").is_err());
+}
+
+#[test]
+fn columns_in_any_order() {
+    let (rows, problems) =
+        spreadsheet::parse("\u{feff}Player,Name,Pronouns\n<@1>,Vex,she/her\n,Rook,\n");
+    assert!(problems.is_empty());
+    assert_eq!(
+        rows,
+        vec![
+            Row {
+                number: 2,
+                name: "Vex".to_string(),
+                pronouns: Some("she/her".to_string()),
+                description: None,
+                image: None,
+                player: Some("<@1>".to_string()),
+            },
+            Row {
+                number: 3,
+                name: "Rook".to_string(),
+                pronouns: None,
+                description: None,
+                image: None,
+                player: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn rows_are_numbered_as_in_a_spreadsheet() {
+    let text = "name,description\n\nVx,\n,\n\"Rook\",\"two\nlines\"\nV,\n";
+    let (rows, problems) = spreadsheet::parse(text);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].number, 5);
+    assert_eq!(
+        problems,
+        vec![
+            "Row 3: the name must be at least 3 characters.",
+            "Row 6: the name must be at least 3 characters.",
+        ]
+    );
+}
+
+#[test]
+fn multi_line_cells_stay_in_one_row() {
+    let text = "name,description\nVex,\"Quiet.\nVery quiet.\n\nGone.\"\nRo,\n";
+    let (rows, problems) = spreadsheet::parse(text);
+    assert_eq!(rows[0].number, 2);
+    assert_eq!(
+        rows[0].description.as_deref(),
+        Some("Quiet.\nVery quiet.\n\nGone.")
+    );
+    assert_eq!(
+        problems,
+        vec!["Row 3: the name must be at least 3 characters."]
+    );
+}
+
+#[test]
+fn row_problems() {
+    let long = "x".repeat(33);
+    let text = format!("name,pronouns\nVx,\n{long},\nVex,{long}\nRook,\nRook,\n");
+    let (rows, problems) = spreadsheet::parse(&text);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(
+        problems,
+        vec![
+            "Row 2: the name must be at least 3 characters.",
+            "Row 3: the name must be at most 32 characters.",
+            "Row 4: the pronouns must be at most 20 characters.",
+            "Row 6: `Rook` appears more than once.",
+        ]
+    );
+
+    let (_, problems) = spreadsheet::parse("title,pronoun\n");
+    assert_eq!(problems.len(), 3);
+}
+
+#[test]
+fn player_ids() {
+    assert_eq!(spreadsheet::user_id("<@123>"), Some(UserId::new(123)));
+    assert_eq!(spreadsheet::user_id("<@!123>"), Some(UserId::new(123)));
+    assert_eq!(spreadsheet::user_id("123"), Some(UserId::new(123)));
+    assert_eq!(spreadsheet::user_id("drowrin"), None);
+}
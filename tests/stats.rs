@@ -0,0 +1,88 @@
+use eurydice::{
+    dice::{Die, Group},
+    stats::{self, DieType},
+};
+
+fn group(sides: u32, values: &[i64]) -> Group {
+    Group {
+        label: format!("{}d{sides}", values.len()),
+        sides,
+        fate: false,
+        dice: values
+            .iter()
+            .map(|&value| Die {
+                value,
+                kept: true,
+                exploded: false,
+                rerolled: false,
+            })
+            .collect(),
+        successes: false,
+        total: values.iter().sum(),
+    }
+}
+
+#[test]
+fn dice_are_grouped_by_type() {
+    let rolls = [
+        vec![group(20, &[20])],
+        vec![group(6, &[1, 2]), group(20, &[1, 15])],
+    ];
+    let summary = stats::summarise(rolls.iter().map(Vec::as_slice));
+
+    assert_eq!(summary.len(), 2);
+    assert_eq!(summary[0].die, DieType::Sides(20));
+    assert_eq!(summary[0].rolled(), 3);
+    assert_eq!(summary[0].average(), 12.0);
+    assert_eq!(summary[0].expected(), 10.5);
+    assert_eq!(summary[0].crits(), Some(1));
+    assert_eq!(summary[0].fumbles(), Some(1));
+    assert_eq!(summary[1].die, DieType::Sides(6));
+}
+
+#[test]
+fn dropped_dice_still_count() {
+    let mut advantage = group(20, &[4, 17]);
+    advantage.dice[0].kept = false;
+    let summary = stats::summarise([vec![advantage]].iter().map(Vec::as_slice));
+
+    assert_eq!(summary[0].rolled(), 2);
+}
+
+#[test]
+fn streaks() {
+    let rolls = [
+        vec![group(6, &[4, 5, 6])],
+        vec![group(6, &[1, 2])],
+        vec![group(6, &[6, 1, 1, 1, 3])],
+    ];
+    let summary = stats::summarise(rolls.iter().map(Vec::as_slice));
+
+    assert_eq!(summary[0].hot_streak, 3);
+    assert_eq!(summary[0].cold_streak, 4);
+}
+
+#[test]
+fn fate_dice() {
+    let mut fate = group(3, &[-1, 0, 1, 1]);
+    fate.fate = true;
+    let summary = stats::summarise([vec![fate]].iter().map(Vec::as_slice));
+
+    assert_eq!(summary[0].die.name(), "dF");
+    assert_eq!(summary[0].counts, [1, 1, 2]);
+    assert_eq!(summary[0].expected(), 0.0);
+    assert_eq!(summary[0].crits(), None);
+}
+
+#[test]
+fn large_dice_are_banded() {
+    let summary = stats::summarise([vec![group(100, &[1, 5, 100])]].iter().map(Vec::as_slice));
+    let distribution = summary[0].distribution();
+
+    assert_eq!(distribution.len(), 20);
+    assert_eq!(distribution[0], (1..=5, 2));
+    assert_eq!(distribution[19], (96..=100, 1));
+
+    let summary = stats::summarise([vec![group(6, &[3])]].iter().map(Vec::as_slice));
+    assert_eq!(summary[0].distribution()[2], (3..=3, 1));
+}